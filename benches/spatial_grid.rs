@@ -0,0 +1,103 @@
+#![feature(test)]
+
+extern crate test;
+
+use bevy::prelude::*;
+use jeremy_bearimy::bees::{BASE_BEE_RADIUS, BEE_INDEX_CELL_SIZE};
+use jeremy_bearimy::spatial::SpatialGrid;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use test::{Bencher, black_box};
+
+const BEES: usize = 10_000;
+const BLADES_RADIUS: f32 = 48.0;
+const FLOCK_RADIUS: f32 = 150.0;
+
+/// Bees spread over an area the size of the Jeremy Bearimy map.
+fn spread_bees() -> Vec<Vec2> {
+	bees_within(Vec2::new(10240.0, 5120.0))
+}
+
+/// Bees crowded around the blades, as when several portals are nearby.
+fn crowded_bees() -> Vec<Vec2> {
+	bees_within(Vec2::splat(1024.0))
+}
+
+fn bees_within(size: Vec2) -> Vec<Vec2> {
+	let mut rng = StdRng::seed_from_u64(0);
+	(0..BEES)
+		.map(|_| (Vec2::new(rng.random(), rng.random()) - 0.5) * size)
+		.collect()
+}
+
+/// The loop `kill_bees` used before the grid.
+fn brute_force(bees: &[Vec2]) -> usize {
+	bees.iter()
+		.filter(|bee| bee.distance(Vec2::ZERO) < BLADES_RADIUS + BASE_BEE_RADIUS)
+		.count()
+}
+
+/// Includes rebuilding the grid, since `index_bees` does that every frame.
+fn grid(grid: &mut SpatialGrid<usize>, bees: &[Vec2]) -> usize {
+	grid.rebuild(bees.iter().copied().zip(0..));
+	grid.query_radius(Vec2::ZERO, BLADES_RADIUS + BASE_BEE_RADIUS)
+		.filter(|(bee, _)| bee.distance(Vec2::ZERO) < BLADES_RADIUS + BASE_BEE_RADIUS)
+		.count()
+}
+
+/// Every bee looking for its neighbors, as flocking does.
+fn brute_force_neighbors(bees: &[Vec2]) -> usize {
+	bees.iter()
+		.map(|bee| {
+			bees.iter()
+				.filter(|other| other.distance_squared(*bee) < FLOCK_RADIUS * FLOCK_RADIUS)
+				.count()
+		})
+		.sum()
+}
+
+fn grid_neighbors(grid: &mut SpatialGrid<usize>, bees: &[Vec2]) -> usize {
+	grid.rebuild(bees.iter().copied().zip(0..));
+	bees.iter()
+		.map(|bee| grid.query_radius(*bee, FLOCK_RADIUS).count())
+		.sum()
+}
+
+#[bench]
+fn brute_force_spread_10k(b: &mut Bencher) {
+	let bees = spread_bees();
+	b.iter(|| brute_force(black_box(&bees)));
+}
+
+#[bench]
+fn grid_spread_10k(b: &mut Bencher) {
+	let bees = spread_bees();
+	let mut index = SpatialGrid::new(BEE_INDEX_CELL_SIZE);
+	b.iter(|| grid(&mut index, black_box(&bees)));
+}
+
+#[bench]
+fn brute_force_crowded_10k(b: &mut Bencher) {
+	let bees = crowded_bees();
+	b.iter(|| brute_force(black_box(&bees)));
+}
+
+#[bench]
+fn grid_crowded_10k(b: &mut Bencher) {
+	let bees = crowded_bees();
+	let mut index = SpatialGrid::new(BEE_INDEX_CELL_SIZE);
+	b.iter(|| grid(&mut index, black_box(&bees)));
+}
+
+#[bench]
+fn brute_force_neighbors_spread_10k(b: &mut Bencher) {
+	let bees = spread_bees();
+	b.iter(|| brute_force_neighbors(black_box(&bees)));
+}
+
+#[bench]
+fn grid_neighbors_spread_10k(b: &mut Bencher) {
+	let bees = spread_bees();
+	let mut index = SpatialGrid::new(BEE_INDEX_CELL_SIZE);
+	b.iter(|| grid_neighbors(&mut index, black_box(&bees)));
+}
@@ -1,23 +1,121 @@
-use crate::GameState;
-use crate::player::Blades;
+use crate::bees::steering::{Steering, SteeringState, steer_bees};
+use crate::gameplay_assets::GameplayAssets;
+use crate::interpolation::InterpolatedTranslation;
+use crate::map::Map;
+use crate::player::{Blades, BladesSweep, SweptBlades, end_blades_sweep, sweep_blades};
+use crate::portals::{SpawnItem, SpawnedItem, spawn_items};
+use crate::spatial::SpatialGrid;
 use crate::stats::LevelStats;
+use crate::{GameState, PlayState};
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub mod steering;
+
+/// The radius of a bee at its sprite's normal size.
+pub const BASE_BEE_RADIUS: f32 = 16.0;
+
+/// Cell size of the [`BeeIndex`] grid, which should be around the size of the usual query radius.
+pub const BEE_INDEX_CELL_SIZE: f32 = 128.0;
+
+/// How long a bee which survives a hit is immune to further hits.
+pub const BEE_HIT_COOLDOWN: Duration = Duration::from_millis(300);
 
 pub struct BeesPlugin;
 
 impl Plugin for BeesPlugin {
 	fn build(&self, _app: &mut App) {
-		_app.add_systems(
-			Update,
-			(move_bees, despawn_bees, kill_bees).run_if(in_state(GameState::Playing)),
-		);
+		_app.init_resource::<BeeIndex>()
+			.register_type::<Bee>()
+			.register_type::<BeeLifetime>()
+			.register_type::<Steering>()
+			.add_systems(
+				FixedUpdate,
+				(
+					spawn_bees.after(spawn_items),
+					(
+						(steer_bees, move_bees, index_bees, kill_bees, despawn_bees)
+							.chain()
+							.after(sweep_blades)
+							.before(end_blades_sweep),
+						tick_hit_cooldowns,
+					)
+						.run_if(in_state(PlayState::Running)),
+				),
+			)
+			.add_systems(
+				PostUpdate,
+				decorate_bees.run_if(resource_exists::<GameplayAssets>),
+			);
 	}
 }
 
 #[derive(Component, Debug, Clone, Reflect)]
-#[require(DespawnTimer, Sprite, StateScoped::<GameState>(GameState::LevelEnd))]
+#[reflect(Component, Default)]
+#[require(
+	BeeLifetime,
+	BeeAge,
+	Sprite,
+	Steering,
+	SteeringState,
+	InterpolatedTranslation,
+	StateScoped::<GameState>(GameState::LevelEnd)
+)]
 pub struct Bee {
 	pub speed: f32,
+	/// Added to the blades' radius when checking for hits.
+	pub radius: f32,
+	/// How many more hits it takes to kill the bee. `None` dies on the first hit.
+	pub health: Option<u32>,
+	/// Added to [`LevelStats::score`] when the bee is killed.
+	pub score: u32,
+}
+
+impl Default for Bee {
+	fn default() -> Self {
+		Self {
+			speed: 400.0,
+			radius: BASE_BEE_RADIUS,
+			health: None,
+			score: 1,
+		}
+	}
+}
+
+pub fn spawn_bees(mut cmds: Commands, mut events: EventReader<SpawnItem>) {
+	for event in events.read() {
+		if let SpawnedItem::Bees {
+			speed,
+			steering,
+			radius,
+			health,
+			score,
+			lifetime,
+		} = event.item
+		{
+			let scale = radius / BASE_BEE_RADIUS;
+			cmds.spawn((
+				Bee {
+					speed,
+					radius,
+					health,
+					score,
+				},
+				steering,
+				lifetime,
+				SteeringState::from_portal(event.portal, event.origin),
+				event.transform.with_scale(Vec3::new(scale, scale, 1.0)),
+			));
+		}
+	}
+}
+
+/// Gives newly added bees their sprite, whether they were spawned by a portal or a level scene.
+pub fn decorate_bees(mut bees: Query<&mut Sprite, Added<Bee>>, assets: Res<GameplayAssets>) {
+	for mut sprite in &mut bees {
+		sprite.image = assets.bee.clone();
+	}
 }
 
 pub fn move_bees(mut query: Query<(&mut Transform, &Bee)>, t: Res<Time>) {
@@ -27,49 +125,190 @@ pub fn move_bees(mut query: Query<(&mut Transform, &Bee)>, t: Res<Time>) {
 	}
 }
 
-#[derive(Component, Debug, Clone, Deref, DerefMut)]
-pub struct DespawnTimer(Timer);
+/// How long a bee lasts, how it fades out, and when it counts as missed.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BeeLifetime {
+	/// How long until the bee despawns. `None` lasts until it is killed or leaves the map.
+	pub duration: Option<Duration>,
+	pub fade: FadeCurve,
+	pub missed_when: MissCondition,
+}
 
-impl Default for DespawnTimer {
+impl Default for BeeLifetime {
 	fn default() -> Self {
-		Self(Timer::from_seconds(0.5, TimerMode::Once))
+		Self {
+			duration: Some(Duration::from_millis(500)),
+			fade: default(),
+			missed_when: default(),
+		}
+	}
+}
+
+/// How a bee's opacity drops over its [`BeeLifetime::duration`].
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[reflect(Default, Serialize, Deserialize)]
+pub enum FadeCurve {
+	/// Stay fully opaque until despawning.
+	Constant,
+	Linear,
+	#[default]
+	Quadratic,
+}
+
+impl FadeCurve {
+	/// The opacity of a bee `fraction` of the way through its lifetime.
+	pub fn alpha(&self, fraction: f32) -> f32 {
+		match self {
+			FadeCurve::Constant => 1.0,
+			FadeCurve::Linear => 1.0 - fraction,
+			FadeCurve::Quadratic => 1.0 - (fraction * fraction),
+		}
 	}
 }
 
+/// When a bee that got away counts towards [`LevelStats::missed_bees`].
+/// Bees are always despawned when they expire or leave the map, whether or not they count as missed.
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[reflect(Default, Serialize, Deserialize)]
+pub enum MissCondition {
+	/// When its [`BeeLifetime::duration`] runs out.
+	#[default]
+	Expired,
+	/// When it leaves the map bounds.
+	LeftBounds,
+	/// When it comes within `radius` of one of the map's [`tuesdays`](Map::tuesdays).
+	ReachedTarget { radius: f32 },
+}
+
+/// How long a bee has existed.
+#[derive(Component, Debug, Default, Copy, Clone, Deref, DerefMut)]
+pub struct BeeAge(pub Duration);
+
 pub fn despawn_bees(
 	mut cmds: Commands,
-	mut query: Query<(Entity, &mut DespawnTimer, &mut Sprite)>,
+	mut query: Query<(Entity, &mut BeeAge, &BeeLifetime, &Transform, &mut Sprite)>,
+	map: Option<Res<Map>>,
 	mut stats: ResMut<LevelStats>,
 	t: Res<Time>,
 ) {
-	for (id, mut timer, mut sprite) in &mut query {
-		timer.tick(t.delta());
-		let percent = timer.elapsed_secs() / timer.duration().as_secs_f32();
-		sprite.color.set_alpha(1.0 - (percent * percent));
-		if timer.finished() {
-			cmds.entity(id).despawn();
+	for (id, mut age, lifetime, xform, mut sprite) in &mut query {
+		**age += t.delta();
+		let pos = xform.translation.xy();
+		let expired = lifetime.duration.is_some_and(|duration| **age >= duration);
+		let out_of_bounds = map.as_deref().is_some_and(|map| {
+			let abs_pos = pos.abs();
+			abs_pos.x > map.size.x * 0.5 || abs_pos.y > map.size.y * 0.5
+		});
+		let missed = match lifetime.missed_when {
+			MissCondition::Expired => expired,
+			MissCondition::LeftBounds => out_of_bounds,
+			MissCondition::ReachedTarget { radius } => map.as_deref().is_some_and(|map| {
+				map.tuesdays
+					.iter()
+					.any(|target| target.distance_squared(pos) < radius * radius)
+			}),
+		};
+		if let Some(duration) = lifetime.duration {
+			let fraction = (age.as_secs_f32() / duration.as_secs_f32()).min(1.0);
+			sprite.color.set_alpha(lifetime.fade.alpha(fraction));
+		}
+		if missed {
 			stats.missed_bees += 1;
 		}
+		if missed || expired || out_of_bounds {
+			cmds.entity(id).despawn();
+		}
+	}
+}
+
+/// Makes a bee immune to hits after surviving one.
+#[derive(Component, Debug, Clone, Deref, DerefMut)]
+pub struct HitCooldown(Timer);
+
+impl Default for HitCooldown {
+	fn default() -> Self {
+		Self(Timer::new(BEE_HIT_COOLDOWN, TimerMode::Once))
 	}
 }
 
+/// Every bee's position as of the last [`index_bees`], for finding bees near a point.
+#[derive(Resource, Debug, Clone)]
+pub struct BeeIndex {
+	pub grid: SpatialGrid<Entity>,
+	/// The largest [`Bee::radius`] in the grid, to pad queries by so they find every bee
+	/// touching the queried area.
+	pub max_radius: f32,
+}
+
+impl Default for BeeIndex {
+	fn default() -> Self {
+		Self {
+			grid: SpatialGrid::new(BEE_INDEX_CELL_SIZE),
+			max_radius: 0.0,
+		}
+	}
+}
+
+pub fn index_bees(mut index: ResMut<BeeIndex>, bees: Query<(Entity, &Transform, &Bee)>) {
+	index.max_radius = bees
+		.iter()
+		.map(|(_, _, bee)| bee.radius)
+		.fold(0.0, f32::max);
+	index.grid.rebuild(
+		bees.iter()
+			.map(|(id, xform, _)| (xform.translation.xy(), id)),
+	);
+}
+
 pub fn kill_bees(
 	mut cmds: Commands,
-	bees: Query<(Entity, &GlobalTransform), With<Bee>>,
-	blades: Query<(&GlobalTransform, &Blades)>,
+	mut bees: Query<(&mut Bee, &mut Sprite), Without<HitCooldown>>,
+	index: Res<BeeIndex>,
+	blades: Query<(&Blades, &BladesSweep)>,
 	mut stats: ResMut<LevelStats>,
 ) {
-	for (xform, blades) in blades {
-		for (id, bee_xform) in &bees {
-			if xform
-				.translation()
-				.xy()
-				.distance(bee_xform.translation().xy())
-				< blades.radius
-			{
-				cmds.entity(id).despawn();
-				stats.killed_bees += 1;
+	for (blades, sweep) in blades {
+		let swept = SweptBlades::new(blades, sweep);
+		for (pos, &id) in index
+			.grid
+			.query_radius(swept.center(), swept.bounding_radius() + index.max_radius)
+		{
+			let Ok((mut bee, mut sprite)) = bees.get_mut(id) else {
+				continue;
+			};
+			if !swept.touches(pos, bee.radius) {
+				continue;
+			}
+			match &mut bee.health {
+				Some(health) if *health > 1 => {
+					*health -= 1;
+					cmds.entity(id).insert(HitCooldown::default());
+					let alpha = sprite.color.alpha();
+					sprite.color = Color::srgba(1.0, 0.4, 0.4, alpha);
+				}
+				_ => {
+					cmds.entity(id).despawn();
+					stats.killed_bees += 1;
+					stats.score += bee.score;
+				}
 			}
 		}
 	}
 }
+
+pub fn tick_hit_cooldowns(
+	mut cmds: Commands,
+	mut bees: Query<(Entity, &mut HitCooldown, &mut Sprite)>,
+	t: Res<Time>,
+) {
+	for (id, mut cooldown, mut sprite) in &mut bees {
+		cooldown.tick(t.delta());
+		if cooldown.finished() {
+			cmds.entity(id).remove::<HitCooldown>();
+			let alpha = sprite.color.alpha();
+			sprite.color = Color::WHITE.with_alpha(alpha);
+		}
+	}
+}
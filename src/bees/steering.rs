@@ -0,0 +1,172 @@
+use crate::bees::{BEE_INDEX_CELL_SIZE, Bee};
+use crate::player::{Avatar, BladesSweep};
+use crate::portals::Portal;
+use crate::spatial::SpatialGrid;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{PI, TAU};
+
+/// How a bee changes direction as it flies. Turn rates are in radians per second.
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+pub enum Steering {
+	/// Keep flying in the direction the bee was spawned facing.
+	#[default]
+	Straight,
+	/// Turn towards the player.
+	Seek { turn_rate: f32 },
+	/// Turn away from any blades closer than `radius`.
+	Flee { radius: f32, turn_rate: f32 },
+	/// Swing back and forth by up to `amplitude` radians around the spawn direction.
+	Wander { amplitude: f32, frequency: f32 },
+	/// Circle the portal the bee was spawned from at `radius`, following it as it moves, or the
+	/// point it was spawned from once the portal is gone.
+	/// Positive `turn_rate` orbits counterclockwise, negative orbits clockwise.
+	Orbit { radius: f32, turn_rate: f32 },
+	/// Boids-style flocking with other flocking bees within `radius`.
+	Flock {
+		radius: f32,
+		separation: f32,
+		alignment: f32,
+		cohesion: f32,
+		turn_rate: f32,
+	},
+}
+
+/// Per-bee state needed by some [`Steering`] behaviours.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct SteeringState {
+	/// Where the bee was spawned from, usually the center of a portal.
+	/// Set to the bee's first position if not given.
+	pub origin: Option<Vec2>,
+	/// The portal the bee was spawned from, if any.
+	pub portal: Option<Entity>,
+	/// The direction the bee was first facing.
+	pub base_angle: Option<f32>,
+	pub age: f32,
+}
+
+impl SteeringState {
+	pub fn from_portal(portal: Entity, origin: Vec2) -> Self {
+		Self {
+			origin: Some(origin),
+			portal: Some(portal),
+			..default()
+		}
+	}
+}
+
+fn heading(xform: &Transform) -> f32 {
+	Vec2::Y.angle_to((xform.rotation * Vec3::Y).xy())
+}
+
+/// Rotates `xform` towards `dir` by no more than `max_turn` radians.
+fn turn_towards(xform: &mut Transform, dir: Vec2, max_turn: f32) {
+	if dir == Vec2::ZERO {
+		return;
+	}
+	let current = heading(xform);
+	let diff = (Vec2::Y.angle_to(dir) - current + PI).rem_euclid(TAU) - PI;
+	xform.rotation = Quat::from_rotation_z(current + diff.clamp(-max_turn, max_turn));
+}
+
+type PlayerTransform<'w> = Single<'w, &'static Transform, (With<Avatar>, Without<Bee>)>;
+
+pub fn steer_bees(
+	mut bees: Query<(Entity, &mut Transform, &Steering, &mut SteeringState), With<Bee>>,
+	player: Option<PlayerTransform>,
+	portals: Query<&Transform, (With<Portal>, Without<Bee>)>,
+	blades: Query<&BladesSweep>,
+	mut flock: Local<Option<SpatialGrid<(Entity, Vec2)>>>,
+	t: Res<Time>,
+) {
+	let dt = t.delta_secs();
+	let player = player.map(|player| player.translation.xy());
+	// Flocking bees need to know about each other while each one is being steered.
+	let flock = flock.get_or_insert_with(|| SpatialGrid::new(BEE_INDEX_CELL_SIZE));
+	flock.rebuild(
+		bees.iter()
+			.filter(|(_, _, steering, _)| matches!(steering, Steering::Flock { .. }))
+			.map(|(id, xform, _, _)| {
+				let pos = xform.translation.xy();
+				(pos, (id, (xform.rotation * Vec3::Y).xy()))
+			}),
+	);
+
+	for (id, mut xform, steering, mut state) in &mut bees {
+		let pos = xform.translation.xy();
+		let origin = *state.origin.get_or_insert(pos);
+		let base_angle = *state.base_angle.get_or_insert_with(|| heading(&xform));
+		state.age += dt;
+		match *steering {
+			Steering::Straight => {}
+			Steering::Seek { turn_rate } => {
+				if let Some(player) = player {
+					turn_towards(&mut xform, player - pos, turn_rate * dt);
+				}
+			}
+			Steering::Flee { radius, turn_rate } => {
+				let away = blades
+					.iter()
+					.map(|blades| pos - blades.to)
+					.filter(|offset| offset.length_squared() < radius * radius)
+					.sum::<Vec2>();
+				turn_towards(&mut xform, away, turn_rate * dt);
+			}
+			Steering::Wander {
+				amplitude,
+				frequency,
+			} => {
+				let angle = base_angle + amplitude * (TAU * frequency * state.age).sin();
+				xform.rotation = Quat::from_rotation_z(angle);
+			}
+			Steering::Orbit { radius, turn_rate } => {
+				let center = state
+					.portal
+					.and_then(|portal| portals.get(portal).ok())
+					.map_or(origin, |portal| portal.translation.xy());
+				let from_center = pos - center;
+				let dist = from_center.length();
+				if dist > 0.0 {
+					let outward = from_center / dist;
+					let tangent = outward.perp() * turn_rate.signum();
+					let dir = tangent - outward * (dist - radius) / radius.max(1.0);
+					turn_towards(&mut xform, dir, turn_rate.abs() * dt);
+				}
+			}
+			Steering::Flock {
+				radius,
+				separation,
+				alignment,
+				cohesion,
+				turn_rate,
+			} => {
+				let mut count = 0;
+				let mut away = Vec2::ZERO;
+				let mut headings = Vec2::ZERO;
+				let mut center = Vec2::ZERO;
+				for (other_pos, &(other, other_dir)) in flock.query_radius(pos, radius) {
+					if other == id {
+						continue;
+					}
+					let offset = pos - other_pos;
+					let dist_sq = offset.length_squared();
+					count += 1;
+					if dist_sq > 0.0 {
+						away += offset / dist_sq;
+					}
+					headings += other_dir;
+					center += other_pos;
+				}
+				if count > 0 {
+					let n = count as f32;
+					let dir = (xform.rotation * Vec3::Y).xy()
+						+ away * separation
+						+ (headings / n) * alignment
+						+ (center / n - pos).normalize_or_zero() * cohesion;
+					turn_towards(&mut xform, dir, turn_rate * dt);
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,167 @@
+//! Plays every level with the [`Bot`] over many seeds and reports how it did, for tuning level
+//! goals and player speed from data rather than by feel.
+//!
+//! Usage: `balance [--seeds <count>] [--level <name>] [--assets <dir>]`
+
+use jeremy_bearimy::arg_value;
+use jeremy_bearimy::bot::Bot;
+use jeremy_bearimy::sim::{SimConfig, SimError, SimOutcome, load_level_list, simulate};
+use std::collections::BTreeMap;
+use std::process::ExitCode;
+use std::thread;
+
+const DEFAULT_SEEDS: u64 = 20;
+
+fn main() -> ExitCode {
+	let seeds = match arg_value("--seeds").map(|seeds| seeds.parse::<u64>()) {
+		None => DEFAULT_SEEDS,
+		Some(Ok(seeds)) => seeds,
+		Some(Err(e)) => {
+			eprintln!("Invalid seed count: {e}");
+			return ExitCode::FAILURE;
+		}
+	};
+	let base = SimConfig {
+		asset_dir: arg_value("--assets").unwrap_or(SimConfig::default().asset_dir),
+		..SimConfig::default()
+	};
+	let level_list = match load_level_list(base.asset_dir.clone()) {
+		Ok(level_list) => level_list,
+		Err(e) => {
+			eprintln!("{e}");
+			return ExitCode::FAILURE;
+		}
+	};
+	let only_level = arg_value("--level");
+	let levels = level_list
+		.iter()
+		.filter(|level| only_level.as_ref().is_none_or(|name| level.name == *name))
+		.map(|level| level.name.clone())
+		.collect::<Vec<_>>();
+	if levels.is_empty() {
+		eprintln!("No level named {:?}", only_level.unwrap_or_default());
+		return ExitCode::FAILURE;
+	}
+
+	println!(
+		"{:<20} {:>7} {:>10} {:>11} {:>9}  Results",
+		"Level", "Wins", "Avg kills", "Avg missed", "Avg time"
+	);
+	let mut failed = false;
+	for level in levels {
+		let configs = (0..seeds)
+			.map(|seed| SimConfig {
+				level: level.clone(),
+				seed: Some(seed),
+				..base.clone()
+			})
+			.collect::<Vec<_>>();
+		let mut outcomes = Vec::new();
+		for result in simulate_all(&configs) {
+			match result {
+				Ok(outcome) => outcomes.push(outcome),
+				Err(e) => {
+					eprintln!("{level}: {e}");
+					failed = true;
+				}
+			}
+		}
+		if !outcomes.is_empty() {
+			println!("{}", Summary::new(&level, &outcomes));
+		}
+	}
+	if failed {
+		ExitCode::FAILURE
+	} else {
+		ExitCode::SUCCESS
+	}
+}
+
+/// Runs each simulation on one of a thread per core.
+fn simulate_all(configs: &[SimConfig]) -> Vec<Result<SimOutcome, SimError>> {
+	let threads = thread::available_parallelism().map_or(1, |n| n.get());
+	thread::scope(|scope| {
+		let workers = (0..threads)
+			.map(|worker| {
+				scope.spawn(move || {
+					configs
+						.iter()
+						.skip(worker)
+						.step_by(threads)
+						.map(|config| simulate(config, Bot::default()))
+						.collect::<Vec<_>>()
+				})
+			})
+			.collect::<Vec<_>>();
+		workers
+			.into_iter()
+			.flat_map(|worker| worker.join().expect("simulation panicked"))
+			.collect()
+	})
+}
+
+struct Summary<'a> {
+	level: &'a str,
+	runs: usize,
+	wins: usize,
+	avg_kills: f32,
+	avg_missed: f32,
+	avg_secs: f32,
+	/// How many runs ended each way. `None` ran out of simulated time without ending.
+	results: BTreeMap<String, usize>,
+}
+
+impl<'a> Summary<'a> {
+	fn new(level: &'a str, outcomes: &[SimOutcome]) -> Self {
+		let n = outcomes.len() as f32;
+		let mut results = BTreeMap::new();
+		for outcome in outcomes {
+			let result = match outcome.result {
+				Some(result) => format!("{result:?}"),
+				None => "Unfinished".to_owned(),
+			};
+			*results.entry(result).or_default() += 1;
+		}
+		Self {
+			level,
+			runs: outcomes.len(),
+			wins: outcomes.iter().filter(|outcome| outcome.won()).count(),
+			avg_kills: outcomes
+				.iter()
+				.map(|outcome| outcome.stats.killed_bees as f32)
+				.sum::<f32>()
+				/ n,
+			avg_missed: outcomes
+				.iter()
+				.map(|outcome| outcome.stats.missed_bees as f32)
+				.sum::<f32>()
+				/ n,
+			avg_secs: outcomes
+				.iter()
+				.map(|outcome| outcome.stats.time.as_secs_f32())
+				.sum::<f32>()
+				/ n,
+			results,
+		}
+	}
+}
+
+impl std::fmt::Display for Summary<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let results = self
+			.results
+			.iter()
+			.map(|(result, count)| format!("{result}: {count}"))
+			.collect::<Vec<_>>()
+			.join(", ");
+		write!(
+			f,
+			"{:<20} {:>7} {:>10.1} {:>11.1} {:>8.1}s  {results}",
+			self.level,
+			format!("{}/{}", self.wins, self.runs),
+			self.avg_kills,
+			self.avg_missed,
+			self.avg_secs,
+		)
+	}
+}
@@ -0,0 +1,106 @@
+//! Checks every level in the level list, and the maps and scenes they use, for mistakes which would
+//! break them in game. Exits with an error if it finds any, so it can be run before committing.
+//!
+//! Usage: `level-check [--assets <dir>]`
+
+use bevy::prelude::*;
+use jeremy_bearimy::arg_value;
+use jeremy_bearimy::loading::asset_load_failure;
+use jeremy_bearimy::map::Map;
+use jeremy_bearimy::sim::{SimConfig, headless_app, wait_for, wait_for_level_list};
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+	let asset_dir = arg_value("--assets").unwrap_or(SimConfig::default().asset_dir);
+	let mut app = headless_app(asset_dir.clone());
+	app.finish();
+	app.cleanup();
+	let level_list = match wait_for_level_list(&mut app) {
+		Ok(level_list) => level_list,
+		Err(e) => {
+			eprintln!("{e}");
+			return ExitCode::FAILURE;
+		}
+	};
+
+	let mut problems = Vec::new();
+	let mut names = HashSet::new();
+	let mut handles = Vec::new();
+	let mut maps = Vec::new();
+	let server = app.world().resource::<AssetServer>().clone();
+	for level in level_list.iter() {
+		if !names.insert(&level.name) {
+			// They would share an entry in `SaveData::unlocked_levels`.
+			problems.push((
+				level.name.clone(),
+				"has the same name as another level".into(),
+			));
+		}
+		problems.extend(
+			level
+				.problems()
+				.into_iter()
+				.map(|problem| (level.name.clone(), problem)),
+		);
+		// Loading the same path twice only loads it once.
+		let map = server.load::<Map>(&level.map);
+		handles.push((
+			level.name.clone(),
+			format!("map {}", level.map),
+			map.clone().untyped(),
+		));
+		maps.push((level.name.clone(), map));
+		handles.push((
+			level.name.clone(),
+			format!("scene {}", level.scene),
+			server.load::<DynamicScene>(&level.scene).untyped(),
+		));
+	}
+
+	let loaded = wait_for(&mut app, |world| {
+		let server = world.resource::<AssetServer>();
+		let mut failures = Vec::new();
+		for (level, asset, handle) in &handles {
+			if let Some(reason) = asset_load_failure(server, handle) {
+				failures.push((level.clone(), format!("{asset} failed to load: {reason}")));
+			} else if !server.is_loaded_with_dependencies(handle) {
+				return None;
+			}
+		}
+		Some(failures)
+	});
+	match loaded {
+		Ok(failures) => problems.extend(failures),
+		Err(e) => problems.push(("".into(), e.to_string())),
+	}
+	// `headless_app` doesn't load map backgrounds, so they have to be checked for separately.
+	let loaded_maps = app.world().resource::<Assets<Map>>();
+	for (level, map) in &maps {
+		let Some(map) = loaded_maps.get(map) else {
+			continue;
+		};
+		if !Path::new(&asset_dir).join(map.background.path()).exists() {
+			problems.push((
+				level.clone(),
+				format!("background {} does not exist", map.background),
+			));
+		}
+	}
+
+	for (level, problem) in &problems {
+		println!("{level}: {problem}");
+	}
+	if problems.is_empty() {
+		println!("All {} levels are OK", level_list.len());
+		ExitCode::SUCCESS
+	} else {
+		println!(
+			"Found {} problems in {} levels",
+			problems.len(),
+			level_list.len()
+		);
+		ExitCode::FAILURE
+	}
+}
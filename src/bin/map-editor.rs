@@ -27,7 +27,7 @@ fn main() {
 			}),
 			EguiPlugin::default(),
 			DefaultInspectorConfigPlugin,
-			MapPlugin,
+			MapPlugin::default(),
 		))
 		.register_type::<DisplaySettings>()
 		.add_systems(Startup, setup)
@@ -0,0 +1,129 @@
+use crate::bees::BEE_INDEX_CELL_SIZE;
+use crate::player::PlayerSpeedParams;
+use crate::sim::{InputSource, SimView};
+use crate::spatial::SpatialGrid;
+use bevy::prelude::*;
+
+/// Plays levels by steering the [`Avatar`](crate::player::Avatar) through the densest clusters of
+/// bees it can reach, favouring those near portals, while keeping inside the map.
+///
+/// Like a player, it can only choose the value of the [`Move`](crate::player::Move) action.
+#[derive(Debug, Clone)]
+pub struct Bot {
+	/// Bees within this distance of each other count as one cluster.
+	pub cluster_radius: f32,
+	/// The longest to lead moving bees by when aiming for them, in seconds.
+	pub max_lead: f32,
+	/// Clusters within this distance of a portal are worth more, as more bees will follow them.
+	pub portal_radius: f32,
+	/// How much more a cluster near a portal is worth.
+	pub portal_bias: f32,
+	/// How far from the edge of the map to stop, leaving room for mistakes.
+	pub margin: f32,
+	/// Where each bee is expected to be when the bot gets to it.
+	predicted: SpatialGrid<()>,
+}
+
+impl Default for Bot {
+	fn default() -> Self {
+		Self {
+			cluster_radius: 96.0,
+			max_lead: 1.0,
+			portal_radius: 600.0,
+			portal_bias: 1.0,
+			margin: 200.0,
+			predicted: SpatialGrid::new(BEE_INDEX_CELL_SIZE),
+		}
+	}
+}
+
+impl Bot {
+	/// Where the bot wants to go next.
+	pub fn target(&mut self, view: &SimView) -> Vec2 {
+		let max_speed = terminal_speed(&view.level.player_speed_params);
+		let bounds = self.bounds(view);
+		let lead = |pos: Vec2| (view.player.distance(pos) / max_speed).min(self.max_lead);
+		self.predicted.rebuild(
+			view.bees
+				.iter()
+				.map(|bee| (bee.position + bee.velocity * lead(bee.position), ()))
+				.filter(|(pos, _)| pos.abs().cmplt(bounds).all()),
+		);
+
+		let mut best = None;
+		let mut best_score = 0.0;
+		for &(pos, _) in self.predicted.points() {
+			let count = self
+				.predicted
+				.query_radius(pos, self.cluster_radius)
+				.count() as f32;
+			let near_portal = view
+				.portals
+				.iter()
+				.any(|portal| portal.distance_squared(pos) < self.portal_radius.powi(2));
+			let bias = if near_portal {
+				1.0 + self.portal_bias
+			} else {
+				1.0
+			};
+			// Discounted by how long it would take to get there.
+			let score = count * bias / (1.0 + view.player.distance(pos) / max_speed);
+			if score > best_score {
+				best_score = score;
+				best = Some(pos);
+			}
+		}
+		best.unwrap_or_else(|| {
+			// Wait by the nearest portal for more bees.
+			view.portals
+				.iter()
+				.copied()
+				.min_by(|a, b| {
+					a.distance_squared(view.player)
+						.total_cmp(&b.distance_squared(view.player))
+				})
+				.unwrap_or(Vec2::ZERO)
+				.clamp(-bounds, bounds)
+		})
+	}
+
+	/// How far from the center of the map the bot is willing to go on each axis.
+	fn bounds(&self, view: &SimView) -> Vec2 {
+		(view.map.size * 0.5 - Vec2::splat(self.margin)).max(Vec2::ZERO)
+	}
+}
+
+impl InputSource for Bot {
+	fn next_input(&mut self, view: &SimView) -> Vec2 {
+		let params = view.level.player_speed_params;
+		let target = self.target(view);
+		let offset = target - view.player;
+		// Arrive at the target, rather than overshooting it at full speed.
+		let arrive_speed = (2.0 * params.accel * offset.length()).sqrt();
+		let desired = offset.normalize_or_zero() * arrive_speed.min(terminal_speed(&params));
+		let mut input = (desired - view.velocity).normalize_or_zero();
+
+		// Brake on any axis where the player couldn't stop before leaving the map.
+		let bounds = self.bounds(view);
+		let speed = view.velocity.abs();
+		let stopping_distance =
+			speed * speed / (2.0 * (params.accel + params.velocity_decay * speed * 0.5));
+		let stop = view.player + view.velocity.signum() * stopping_distance;
+		if stop.x.abs() > bounds.x {
+			input.x = -stop.x.signum();
+		}
+		if stop.y.abs() > bounds.y {
+			input.y = -stop.y.signum();
+		}
+		input.clamp_length_max(1.0)
+	}
+}
+
+/// The speed the player can't accelerate past, with velocity decay balancing out acceleration.
+fn terminal_speed(params: &PlayerSpeedParams) -> f32 {
+	if params.velocity_decay > 0.0 {
+		(params.accel / params.velocity_decay).min(params.max_velocity)
+	} else {
+		params.max_velocity
+	}
+}
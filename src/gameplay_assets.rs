@@ -0,0 +1,61 @@
+use crate::GameState;
+use crate::loading::{LoadingTasks, report_asset_progress};
+use bevy::prelude::*;
+
+pub struct GameplayAssetsPlugin;
+
+impl Plugin for GameplayAssetsPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(OnEnter(GameState::Loading), GameplayAssets::load)
+			.add_systems(
+				Update,
+				GameplayAssets::check_progress.run_if(in_state(GameState::Loading)),
+			);
+	}
+}
+
+/// Assets used while playing a level, other than the player's own.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct GameplayAssets {
+	pub bee: Handle<Image>,
+	pub portal: Handle<Image>,
+	pub portal_swirls: Handle<Image>,
+	pub font: Handle<Font>,
+}
+
+impl GameplayAssets {
+	pub fn load(
+		mut cmds: Commands,
+		mut loading_tasks: ResMut<LoadingTasks>,
+		server: Res<AssetServer>,
+	) {
+		let _ = loading_tasks.start("Gameplay Assets");
+		cmds.insert_resource(Self {
+			bee: server.load("bee.png"),
+			portal: server.load("portal.png"),
+			portal_swirls: server.load("portal_swirls.png"),
+			font: server.load("ShareTechMono-Regular.ttf"),
+		});
+	}
+
+	pub fn check_progress(
+		assets: Res<GameplayAssets>,
+		server: Res<AssetServer>,
+		mut loading_tasks: ResMut<LoadingTasks>,
+	) {
+		let Some(task) = loading_tasks.find("Gameplay Assets") else {
+			return;
+		};
+		report_asset_progress(
+			&mut loading_tasks,
+			task,
+			&server,
+			&[
+				assets.bee.clone().untyped(),
+				assets.portal.clone().untyped(),
+				assets.portal_swirls.clone().untyped(),
+				assets.font.clone().untyped(),
+			],
+		);
+	}
+}
@@ -1,8 +1,9 @@
 use crate::GameState;
-use crate::levels::{Goal, Level};
+use crate::gameplay_assets::GameplayAssets;
+use crate::levels::{Goal, GoalStatus, Level};
 use crate::player::spawn_player;
 use crate::stats::LevelStats;
-use bevy::color::palettes::basic::YELLOW;
+use bevy::color::palettes::basic::{GREEN, RED, YELLOW};
 use bevy::color::palettes::css::ORANGE;
 use bevy::prelude::*;
 
@@ -16,15 +17,14 @@ impl Plugin for HudPlugin {
 		)
 		.add_systems(
 			Update,
-			update_stats_display.run_if(in_state(GameState::Playing)),
+			(update_stats_display, update_goal_display).run_if(in_state(GameState::Playing)),
 		);
 	}
 }
 
-pub fn spawn_display(mut cmds: Commands, server: Res<AssetServer>, level: Res<Level>) {
-	let font = server.load::<Font>("ShareTechMono-Regular.ttf");
+pub fn spawn_display(mut cmds: Commands, assets: Res<GameplayAssets>, level: Res<Level>) {
 	let font = TextFont {
-		font,
+		font: assets.font.clone(),
 		font_size: 24.0,
 		..default()
 	};
@@ -38,6 +38,12 @@ pub fn spawn_display(mut cmds: Commands, server: Res<AssetServer>, level: Res<Le
 	};
 	let bg_color = BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.7));
 	let scope = StateScoped::<GameState>(GameState::LevelEnd);
+	let goals = level.goal.leaves();
+	let highlight = |active: bool| Outline {
+		width: Val::Px(2.0),
+		color: if active { Color::WHITE } else { Color::NONE },
+		..default()
+	};
 
 	cmds.spawn((
 		Node {
@@ -48,15 +54,7 @@ pub fn spawn_display(mut cmds: Commands, server: Res<AssetServer>, level: Res<Le
 			..panel.clone()
 		},
 		bg_color,
-		Outline {
-			width: Val::Px(2.0),
-			color: if let Goal::Time = level.goal {
-				Color::WHITE
-			} else {
-				Color::NONE
-			},
-			..default()
-		},
+		highlight(goals.iter().any(|goal| matches!(goal, Goal::Time))),
 		scope.clone(),
 	))
 	.with_child((TimeDisplay, Text("0.00".into()), font.clone()));
@@ -72,15 +70,11 @@ pub fn spawn_display(mut cmds: Commands, server: Res<AssetServer>, level: Res<Le
 		cmds.spawn((
 			panel.clone(),
 			bg_color,
-			Outline {
-				width: Val::Px(2.0),
-				color: if let Goal::Bees(_) = level.goal {
-					Color::WHITE
-				} else {
-					Color::NONE
-				},
-				..default()
-			},
+			highlight(
+				goals
+					.iter()
+					.any(|goal| matches!(goal, Goal::Bees(_) | Goal::KillRate(_))),
+			),
 			scope.clone(),
 		))
 		.with_child((
@@ -97,24 +91,42 @@ pub fn spawn_display(mut cmds: Commands, server: Res<AssetServer>, level: Res<Le
 		cmds.spawn((
 			panel,
 			bg_color,
-			Outline {
-				width: Val::Px(2.0),
-				color: if let Goal::MaxMissed(_) = level.goal {
-					Color::WHITE
-				} else {
-					Color::NONE
-				},
-				..default()
-			},
-			scope,
+			highlight(goals.iter().any(|goal| matches!(goal, Goal::MaxMissed(_)))),
+			scope.clone(),
 		))
 		.with_child((
 			MissedBeesDisplay,
 			Text("Missed: 0".into()),
-			font,
+			font.clone(),
 			TextColor(ORANGE.into()),
 		));
 	});
+
+	// Progress towards every part of the goal
+	cmds.spawn((
+		Node {
+			flex_direction: FlexDirection::Column,
+			position_type: PositionType::Absolute,
+			top: Val::Px(10.0),
+			left: Val::Px(10.0),
+			padding: UiRect::all(Val::Px(10.0)),
+			row_gap: Val::Px(4.0),
+			..default()
+		},
+		bg_color,
+		scope,
+	))
+	.with_children(|cmds| {
+		if let Goal::All(_) | Goal::Any(_) = level.goal {
+			cmds.spawn((
+				Text(level.goal.progress_text(&default(), level.duration)),
+				font.clone(),
+			));
+		}
+		for i in 0..goals.len() {
+			cmds.spawn((GoalProgressDisplay(i), font.clone()));
+		}
+	});
 }
 
 pub fn update_stats_display(
@@ -126,18 +138,38 @@ pub fn update_stats_display(
 ) {
 	let rem = level.duration.checked_sub(stats.time).unwrap_or_default();
 	time_display.0 = format!("{:.2}", rem.as_secs_f32());
-	let bee_goal = match level.goal {
-		Goal::Bees(n) => format!("/{n}"),
+	let goals = level.goal.leaves();
+	let bee_goal = match goals.iter().find(|goal| matches!(goal, Goal::Bees(_))) {
+		Some(Goal::Bees(n)) => format!("/{n}"),
 		_ => "".to_owned(),
 	};
 	bee_count_display.0 = format!("Killed: {}{}", stats.killed_bees, bee_goal);
-	let missed_goal = match level.goal {
-		Goal::MaxMissed(n) => format!("/{n}"),
+	let missed_goal = match goals.iter().find(|goal| matches!(goal, Goal::MaxMissed(_))) {
+		Some(Goal::MaxMissed(n)) => format!("/{n}"),
 		_ => "".to_owned(),
 	};
 	missed_bees_display.0 = format!("Missed: {}{}", stats.missed_bees, missed_goal);
 }
 
+pub fn update_goal_display(
+	level: Res<Level>,
+	stats: Res<LevelStats>,
+	mut displays: Query<(&mut Text, &mut TextColor, &GoalProgressDisplay)>,
+) {
+	let goals = level.goal.leaves();
+	for (mut text, mut color, display) in &mut displays {
+		let Some(goal) = goals.get(display.0) else {
+			continue;
+		};
+		text.0 = goal.progress_text(&stats, level.duration);
+		color.0 = match goal.status(&stats, level.duration) {
+			GoalStatus::Won => GREEN.into(),
+			GoalStatus::Failed(_) => RED.into(),
+			GoalStatus::Satisfied | GoalStatus::InProgress => Color::WHITE,
+		};
+	}
+}
+
 #[derive(Component, Debug, Copy, Clone)]
 #[require(Text)]
 pub struct TimeDisplay;
@@ -164,3 +196,8 @@ type MissedBeesDisplayQueryFilter = (
 	Without<TimeDisplay>,
 	Without<KilledBeesDisplay>,
 );
+
+/// Shows progress towards one of the [`Goal::leaves`] of the level's goal.
+#[derive(Component, Debug, Copy, Clone)]
+#[require(Text)]
+pub struct GoalProgressDisplay(pub usize);
@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+
+/// Gameplay runs in [`FixedUpdate`] so that it doesn't depend on the frame rate, which would make
+/// anything it moves stutter whenever frames don't line up with fixed steps. Entities with
+/// [`InterpolatedTranslation`] are instead drawn between where they were in the last two steps.
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(
+			RunFixedMainLoop,
+			(
+				restore_translations.in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+				interpolate_translations.in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+			),
+		)
+		.add_systems(FixedFirst, store_previous_translations)
+		.add_systems(FixedLast, store_current_translations);
+	}
+}
+
+/// Only the translation is interpolated, so systems in [`Update`] can still rotate and scale
+/// things for effect. Anything they do to the translation is overwritten before the next step.
+#[derive(Component, Debug, Default, Copy, Clone)]
+pub struct InterpolatedTranslation {
+	/// Where the entity was at the start of the last fixed step.
+	previous: Option<Vec3>,
+	/// Where the entity was at the end of the last fixed step.
+	current: Option<Vec3>,
+}
+
+/// Puts everything back where the last fixed step left it, so gameplay never sees an interpolated position.
+pub fn restore_translations(mut query: Query<(&mut Transform, &InterpolatedTranslation)>) {
+	for (mut xform, interpolated) in &mut query {
+		if let Some(current) = interpolated.current {
+			xform.translation = current;
+		}
+	}
+}
+
+pub fn store_previous_translations(mut query: Query<(&Transform, &mut InterpolatedTranslation)>) {
+	for (xform, mut interpolated) in &mut query {
+		interpolated.previous = Some(xform.translation);
+	}
+}
+
+pub fn store_current_translations(mut query: Query<(&Transform, &mut InterpolatedTranslation)>) {
+	for (xform, mut interpolated) in &mut query {
+		interpolated.current = Some(xform.translation);
+		// Spawned during the step, so there is nowhere to interpolate from yet.
+		interpolated.previous.get_or_insert(xform.translation);
+	}
+}
+
+pub fn interpolate_translations(
+	mut query: Query<(&mut Transform, &InterpolatedTranslation)>,
+	fixed_time: Res<Time<Fixed>>,
+) {
+	let s = fixed_time.overstep_fraction();
+	for (mut xform, interpolated) in &mut query {
+		if let InterpolatedTranslation {
+			previous: Some(previous),
+			current: Some(current),
+		} = *interpolated
+		{
+			xform.translation = previous.lerp(current, s);
+		}
+	}
+}
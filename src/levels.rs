@@ -1,25 +1,35 @@
-use crate::GameState;
-use crate::loading::LoadingTasks;
+use crate::gameplay_assets::GameplayAssets;
+use crate::loading::{LoadingTasks, RetryLoadingButton, asset_load_failure};
 use crate::map::{Background, Map};
+use crate::medals::MedalThresholds;
 use crate::player::PlayerSpeedParams;
-use crate::portals::{PortalDescriptor, PortalSwirls};
+use crate::portals::{Portal, PortalDescriptor, SpawnedItem};
 use crate::save::SaveData;
-use crate::stats::{GameResult, LevelStats, RunStats, end_level};
+use crate::stats::{GameResult, LevelStats, RunStats, end_level, save_run};
+use crate::{GameState, PlayState};
 use bevy::asset::{AssetPath, ReflectAsset};
 use bevy::prelude::*;
 use bevy_common_assets::ron::RonAssetPlugin;
 use bevy_persistent::Persistent;
 use serde::{Deserialize, Serialize};
-use std::sync::OnceLock;
 use std::time::Duration;
 
+pub mod countdown;
+
 pub struct LevelsPlugin;
 
-pub static LEVEL_LIST_HANDLE: OnceLock<Handle<LevelList>> = OnceLock::new();
+/// How long to wait for a level's map or scene before giving up.
+pub const LEVEL_LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Kept in a resource rather than a static so that more than one [`App`] can load levels,
+/// as simulations do.
+#[derive(Resource, Debug, Clone, Deref)]
+pub struct LevelListHandle(pub Handle<LevelList>);
 
 impl Plugin for LevelsPlugin {
 	fn build(&self, app: &mut App) {
 		app.add_plugins(RonAssetPlugin::<LevelList>::new(&["ron"]))
+			.add_plugins(countdown::CountdownPlugin)
 			.init_asset::<LevelList>()
 			.register_asset_reflect::<LevelList>()
 			.add_systems(
@@ -33,21 +43,32 @@ impl Plugin for LevelsPlugin {
 				Update,
 				(
 					check_level_loading_progress.run_if(in_state(GameState::Loading)),
-					(handle_main_menu_btn, handle_next_level_btn)
-						.run_if(in_state(GameState::LevelEnd)),
+					handle_main_menu_btn.run_if(
+						in_state(GameState::LevelEnd)
+							.or(in_state(GameState::LoadFailed))
+							.or(in_state(PlayState::Paused)),
+					),
+					handle_next_level_btn.run_if(in_state(GameState::LevelEnd)),
+					handle_retry_loading_btn.run_if(in_state(GameState::LoadFailed)),
 				),
 			)
-			.add_systems(Last, check_goal.run_if(in_state(GameState::Playing)))
-			.add_systems(OnEnter(GameState::Playing), start_wave)
+			.add_systems(
+				FixedUpdate,
+				advance_waves.run_if(in_state(PlayState::Running)),
+			)
+			// After everything in `FixedUpdate` has had a chance to change the stats.
+			.add_systems(
+				FixedPostUpdate,
+				check_goal.run_if(in_state(PlayState::Running)),
+			)
+			.add_systems(OnEnter(GameState::Playing), (start_wave, spawn_level_scene))
 			.add_systems(OnEnter(GameState::LevelEnd), show_level_end_screen);
 
-		LEVEL_LIST_HANDLE
-			.set(
-				app.world()
-					.resource::<AssetServer>()
-					.load("levels/level_list.ron"),
-			)
-			.expect("no other calls to set");
+		let handle = app
+			.world()
+			.resource::<AssetServer>()
+			.load("levels/level_list.ron");
+		app.insert_resource(LevelListHandle(handle));
 	}
 }
 
@@ -61,11 +82,17 @@ pub struct Level {
 	#[reflect(ignore)]
 	#[serde(skip)]
 	pub map_handle: Handle<Map>,
+	#[reflect(ignore)]
+	#[serde(skip)]
+	pub scene_handle: Handle<DynamicScene>,
 	pub goal: Goal,
 	pub duration: Duration,
 	pub waves: Vec<Wave>,
 	pub current_wave: usize,
 	pub player_speed_params: PlayerSpeedParams,
+	pub medals: Option<MedalThresholds>,
+	/// Makes every attempt play out the same way, as long as the player does.
+	pub seed: Option<u64>,
 }
 
 impl Default for Level {
@@ -75,20 +102,120 @@ impl Default for Level {
 			map: AssetPath::from("maps/map.ron"),
 			scene: AssetPath::from("levels/empty.scn.ron"),
 			map_handle: Handle::default(),
+			scene_handle: Handle::default(),
 			goal: Goal::Bees(500),
 			duration: Duration::from_secs(120),
-			waves: vec![Wave {
-				portals: vec![PortalDescriptor::default()],
-			}],
+			waves: vec![Wave::default()],
 			current_wave: 0,
 			player_speed_params: default(),
+			medals: None,
+			seed: None,
+		}
+	}
+}
+
+impl Level {
+	/// Mistakes in the level's description which would break it once loaded.
+	pub fn problems(&self) -> Vec<String> {
+		let mut problems = Vec::new();
+		if self.waves.is_empty() {
+			problems.push("has no waves".to_owned());
+		}
+		for (i, wave) in self.waves.iter().enumerate() {
+			for (j, portal) in wave.portals.iter().enumerate() {
+				portal_problems(portal, &format!("wave {i} portal {j}"), true, &mut problems);
+			}
+			let last = i + 1 == self.waves.len();
+			if !last
+				&& let WaveTrigger::PortalsExhausted = wave.advance_on
+				&& wave.portals.iter().any(PortalDescriptor::is_unlimited)
+			{
+				problems.push(format!(
+					"wave {i} waits for its portals to run out, but some never do"
+				));
+			}
+		}
+		problems
+	}
+}
+
+/// Sub-portals don't move, so only portals which `moves` need a speed.
+fn portal_problems(portal: &PortalDescriptor, name: &str, moves: bool, problems: &mut Vec<String>) {
+	if moves && portal.speed == 0.0 {
+		problems.push(format!("{name} has zero speed"));
+	}
+	if portal.spawn_interval.is_zero() {
+		problems.push(format!("{name} has zero spawn interval"));
+	}
+	if let SpawnedItem::SubPortals(sub_portals) = &portal.spawns {
+		for (i, sub_portal) in sub_portals.iter().enumerate() {
+			portal_problems(
+				sub_portal,
+				&format!("{name} sub-portal {i}"),
+				false,
+				problems,
+			);
 		}
 	}
 }
 
 #[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+#[reflect(Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Wave {
 	pub portals: Vec<PortalDescriptor>,
+	/// When to move on to the next wave. Ignored for the last wave of a level.
+	pub advance_on: WaveTrigger,
+	/// How long to wait between this wave's portals closing and the next wave's opening.
+	pub break_duration: Duration,
+}
+
+impl Default for Wave {
+	fn default() -> Self {
+		Self {
+			portals: vec![PortalDescriptor::default()],
+			advance_on: default(),
+			break_duration: Duration::ZERO,
+		}
+	}
+}
+
+#[derive(Reflect, Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[reflect(Default, Debug, Serialize, Deserialize)]
+pub enum WaveTrigger {
+	/// Advance once the wave has been running for this long.
+	Time(Duration),
+	/// Advance once this many bees have been killed during the wave.
+	Kills(u32),
+	/// Advance once every portal in the wave has run out of spawns.
+	#[default]
+	PortalsExhausted,
+}
+
+/// Marks portals spawned by a [`Wave`], as opposed to ones placed some other way,
+/// so they can be cleaned up when the wave ends.
+#[derive(Component, Debug, Default, Copy, Clone)]
+pub struct WavePortal;
+
+/// Tracks the state of the current wave within a level.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct WaveProgress {
+	/// Value of [`LevelStats::time`] when the current wave started.
+	pub started_at: Duration,
+	/// Value of [`LevelStats::killed_bees`] when the current wave started.
+	pub kills_at_start: u32,
+	/// Counts down the break before the next wave, if the current one has ended.
+	pub break_timer: Option<Timer>,
+}
+
+impl WaveProgress {
+	pub fn new(stats: &LevelStats) -> Self {
+		Self {
+			started_at: stats.time,
+			kills_at_start: stats.killed_bees,
+			break_timer: None,
+		}
+	}
 }
 
 pub fn load_level(
@@ -111,61 +238,154 @@ pub fn load_level(
 		"load_level should be run on resource_added::<Level>"
 	);
 	info!("Loading level {}", level.name);
+	for problem in level.problems() {
+		warn!("Level {} {problem}", level.name);
+	}
 	next_state.set(GameState::Loading);
-	let _ = loading_tasks.start("Map");
+	let _ = loading_tasks.start_with_timeout("Map", LEVEL_LOAD_TIMEOUT);
 	level.map_handle = server.load(&level.map);
+	let _ = loading_tasks.start_with_timeout("Scene", LEVEL_LOAD_TIMEOUT);
+	level.scene_handle = server.load(&level.scene);
 }
 
 pub fn check_level_loading_progress(
+	level: Res<Level>,
 	map: Option<Res<Map>>,
+	server: Res<AssetServer>,
 	mut loading_tasks: ResMut<LoadingTasks>,
 ) {
+	let map_task = loading_tasks.find("Map").unwrap();
 	if map.is_some() {
-		let handle = loading_tasks.find("Map").unwrap();
-		loading_tasks.finish(handle);
+		loading_tasks.finish(map_task);
+	} else if let Some(reason) = asset_load_failure(&server, &level.map_handle) {
+		loading_tasks.fail(map_task, reason);
+	}
+	let scene_task = loading_tasks.find("Scene").unwrap();
+	if server.is_loaded_with_dependencies(level.scene_handle.id()) {
+		loading_tasks.finish(scene_task);
+	} else if let Some(reason) = asset_load_failure(&server, &level.scene_handle) {
+		loading_tasks.fail(scene_task, reason);
+	}
+}
+
+/// Loads the current level again after a loading task failed.
+pub fn handle_retry_loading_btn(
+	mut cmds: Commands,
+	level: Res<Level>,
+	interaction: Single<&Interaction, With<RetryLoadingButton>>,
+) {
+	if **interaction == Interaction::Pressed {
+		info!("Retrying loading level {}", level.name);
+		cmds.remove_resource::<Map>();
+		cmds.remove_resource::<Level>();
+		cmds.insert_resource(level.clone());
+	}
+}
+
+/// Marks the root entity of a level's [`Level::scene`].
+#[derive(Component, Debug, Default, Copy, Clone)]
+#[require(StateScoped::<GameState>(GameState::LevelEnd))]
+pub struct LevelScene;
+
+/// Despawns the entities which are normally cleaned up when leaving [`GameState::LevelEnd`],
+/// for when a level is left before it ends.
+pub fn despawn_level_entities(
+	mut cmds: Commands,
+	scoped: Query<(Entity, &StateScoped<GameState>)>,
+) {
+	for (id, scope) in &scoped {
+		if scope.0 == GameState::LevelEnd {
+			cmds.entity(id).despawn();
+		}
+	}
+}
+
+pub fn spawn_level_scene(mut cmds: Commands, level: Res<Level>) {
+	cmds.spawn((LevelScene, DynamicSceneRoot(level.scene_handle.clone())));
+}
+
+pub fn start_wave(mut cmds: Commands, mut level: ResMut<Level>, stats: Res<LevelStats>) {
+	// Levels can be retried without being reloaded, so always start over from the first wave.
+	level.current_wave = 0;
+	cmds.insert_resource(WaveProgress::new(&stats));
+	spawn_wave_portals(&mut cmds, &level);
+}
+
+pub fn advance_waves(
+	mut cmds: Commands,
+	mut level: ResMut<Level>,
+	mut progress: ResMut<WaveProgress>,
+	stats: Res<LevelStats>,
+	portals: Query<(Entity, &Portal), With<WavePortal>>,
+	t: Res<Time>,
+) {
+	if let Some(timer) = &mut progress.break_timer {
+		timer.tick(t.delta());
+		if timer.finished() {
+			level.current_wave += 1;
+			*progress = WaveProgress::new(&stats);
+			spawn_wave_portals(&mut cmds, &level);
+		}
+		return;
+	}
+
+	if level.current_wave + 1 >= level.waves.len() {
+		return;
+	}
+	let wave = &level.waves[level.current_wave];
+	let finished = match wave.advance_on {
+		WaveTrigger::Time(duration) => stats.time.saturating_sub(progress.started_at) >= duration,
+		WaveTrigger::Kills(n) => stats.killed_bees.saturating_sub(progress.kills_at_start) >= n,
+		WaveTrigger::PortalsExhausted => portals.iter().all(|(_, portal)| portal.is_exhausted()),
+	};
+	if !finished {
+		return;
+	}
+
+	info!("Wave {} finished", level.current_wave);
+	for (id, _) in &portals {
+		cmds.entity(id).despawn();
+	}
+	if wave.break_duration.is_zero() {
+		level.current_wave += 1;
+		*progress = WaveProgress::new(&stats);
+		spawn_wave_portals(&mut cmds, &level);
+	} else {
+		progress.break_timer = Some(Timer::new(wave.break_duration, TimerMode::Once));
 	}
 }
 
-pub fn start_wave(mut cmds: Commands, level: Res<Level>, server: Res<AssetServer>) {
-	level.waves[level.current_wave]
-		.portals
-		.iter()
-		.for_each(|portal| {
-			cmds.spawn((
-				portal.bundle(),
-				Sprite {
-					// TODO: Load this in loading state
-					image: server.load("portal.png"),
-					..default()
-				},
-			))
-			.with_child((
-				PortalSwirls,
-				Sprite {
-					// TODO: Load this in loading state
-					image: server.load("portal_swirls.png"),
-					..default()
-				},
-			));
-		});
+fn spawn_wave_portals(cmds: &mut Commands, level: &Level) {
+	let Some(wave) = level.waves.get(level.current_wave) else {
+		error!("Level {} has no wave {}", level.name, level.current_wave);
+		return;
+	};
+	info!("Starting wave {}", level.current_wave);
+	wave.portals.iter().for_each(|portal| {
+		cmds.spawn((portal.bundle(), WavePortal));
+	});
 }
 
 pub fn show_level_end_screen(
 	mut cmds: Commands,
+	level: Res<Level>,
 	level_list: Res<LevelList>,
+	save: Option<Res<Persistent<SaveData>>>,
 	stats: Res<LevelStats>,
 	run_stats: Res<RunStats>,
 	mut next_state: ResMut<NextState<GameState>>,
-	server: Res<AssetServer>,
+	assets: Res<GameplayAssets>,
 ) {
 	info!("{stats:#?}");
 	info!("{run_stats:#?}");
 	cmds.spawn((Camera2d, StateScoped::<GameState>(GameState::LevelEnd)));
-	let font = server.load::<Font>("ShareTechMono-Regular.ttf");
+	let font = assets.font.clone();
 
 	// Result display
 	cmds.spawn((
 		Node {
+			flex_direction: FlexDirection::Column,
+			align_items: AlignItems::Center,
 			position_type: PositionType::Absolute,
 			align_self: AlignSelf::Center,
 			justify_self: JustifySelf::Center,
@@ -181,6 +401,10 @@ pub fn show_level_end_screen(
 			font_size: 48.0,
 			..default()
 		};
+		let medal_font = TextFont {
+			font_size: 28.0,
+			..font.clone()
+		};
 		let fail_color = TextColor(Color::srgb(0.8, 0.2, 0.2));
 		match stats.result {
 			Some(GameResult::Win) => {
@@ -197,17 +421,59 @@ pub fn show_level_end_screen(
 					fail_color,
 				));
 			}
+			Some(GameResult::HitHazard) => {
+				cmds.spawn((
+					Text("Ouch! You hit a hazard...".into()),
+					font.clone(),
+					fail_color,
+				));
+			}
 			Some(GameResult::TimedOut) => {
 				cmds.spawn((Text("Time's up!".into()), font.clone(), fail_color));
 			}
 			Some(GameResult::MissedTooMany) => {
 				cmds.spawn((Text("You missed too many bees!".into()), font, fail_color));
 			}
+			Some(GameResult::KillRateTooLow) => {
+				cmds.spawn((
+					Text("You didn't kill bees fast enough!".into()),
+					font,
+					fail_color,
+				));
+			}
+			Some(GameResult::OutOfLives) => {
+				cmds.spawn((Text("You ran out of lives!".into()), font, fail_color));
+			}
+			Some(GameResult::Quit) => {
+				cmds.spawn((Text("Level abandoned".into()), font, fail_color));
+			}
 			None => {
 				error!("Result should exist");
 				next_state.set(GameState::MainMenu);
 			}
 		}
+
+		if let Some(medals) = level.medals {
+			match medals.medal(&stats) {
+				Some(medal) => {
+					cmds.spawn((
+						Text(format!("{medal} medal!")),
+						medal_font.clone(),
+						TextColor(medal.color()),
+					));
+				}
+				None => {
+					cmds.spawn((Text("No medal".into()), medal_font.clone()));
+				}
+			}
+			let best = save
+				.as_ref()
+				.and_then(|save| save.personal_bests.get(&level.name))
+				.and_then(|best| best.medal);
+			if let Some(next) = medals.next_goal(best) {
+				cmds.spawn((Text(format!("Next medal - {next}")), medal_font));
+			}
+		}
 	});
 
 	let font = TextFont {
@@ -276,11 +542,26 @@ pub struct MainMenuButton;
 pub fn handle_main_menu_btn(
 	mut cmds: Commands,
 	interaction: Single<&Interaction, With<MainMenuButton>>,
-	background: Single<Entity, With<Background>>,
+	background: Option<Single<Entity, With<Background>>>,
+	state: Res<State<GameState>>,
+	level: Option<Res<Level>>,
+	stats: Option<ResMut<LevelStats>>,
+	run_stats: Option<ResMut<RunStats>>,
 	mut next_state: ResMut<NextState<GameState>>,
 ) {
 	if **interaction == Interaction::Pressed {
-		cmds.entity(*background).despawn();
+		// Quitting from the pause menu still counts as an attempt at the level.
+		if **state == GameState::Playing
+			&& let (Some(level), Some(mut stats), Some(mut run_stats)) = (level, stats, run_stats)
+		{
+			stats.result = Some(GameResult::Quit);
+			run_stats.levels.insert(level.name.clone(), stats.clone());
+		}
+		cmds.run_system_cached(save_run);
+		cmds.run_system_cached(despawn_level_entities);
+		if let Some(background) = background {
+			cmds.entity(*background).despawn();
+		}
 		cmds.remove_resource::<Level>();
 		cmds.remove_resource::<Map>();
 		next_state.set(GameState::MainMenu);
@@ -326,8 +607,11 @@ pub struct LevelList(
 	pub usize,
 );
 
-pub fn insert_loaded_level_list(mut cmds: Commands, assets: Res<Assets<LevelList>>) {
-	let handle = LEVEL_LIST_HANDLE.get().unwrap().clone();
+pub fn insert_loaded_level_list(
+	mut cmds: Commands,
+	handle: Res<LevelListHandle>,
+	assets: Res<Assets<LevelList>>,
+) {
 	let Some(level_list) = assets.get(handle.id()) else {
 		return;
 	};
@@ -335,8 +619,8 @@ pub fn insert_loaded_level_list(mut cmds: Commands, assets: Res<Assets<LevelList
 	cmds.insert_resource(level_list.clone());
 }
 
-#[derive(Reflect, Debug, Clone, Copy, Serialize, Deserialize)]
-#[reflect(Debug, Serialize, Deserialize)]
+#[derive(Reflect, Debug, Clone, Serialize, Deserialize)]
+#[reflect(no_field_bounds, Debug, Serialize, Deserialize)]
 pub enum Goal {
 	/// Win by avoiding going out of bounds for the duration of the level.
 	Time,
@@ -344,28 +628,194 @@ pub enum Goal {
 	Bees(u32),
 	/// Don't miss this many bees.
 	MaxMissed(u32),
+	/// Kill at least this many bees per second, on average, over the whole level.
+	KillRate(f32),
+	/// Survive the level with this many lives. Going out of bounds costs a life
+	/// instead of ending the level immediately.
+	Lives(u32),
+	/// Reach this score.
+	Score(u32),
+	/// Win once every one of these goals is won, but lose if any of them is failed.
+	All(Vec<Goal>),
+	/// Win once any one of these goals is won, but lose if all of them are failed.
+	Any(Vec<Goal>),
 }
 
-pub fn check_goal(mut cmds: Commands, level: Res<Level>, stats: Res<LevelStats>) {
-	match level.goal {
-		Goal::Time => {
-			if stats.time >= level.duration {
-				cmds.run_system_cached_with(end_level, GameResult::Win);
+/// The state of a [`Goal`] at some point during a level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalStatus {
+	/// Not yet achieved.
+	InProgress,
+	/// Currently achieved, but could still be failed before the level ends.
+	Satisfied,
+	Won,
+	Failed(GameResult),
+}
+
+impl GoalStatus {
+	pub fn is_achieved(&self) -> bool {
+		matches!(self, Self::Satisfied | Self::Won)
+	}
+}
+
+impl Goal {
+	pub fn status(&self, stats: &LevelStats, duration: Duration) -> GoalStatus {
+		let out_of_time = stats.time >= duration;
+		match self {
+			// Only achieved once the time is up, so `All` can't be won before then.
+			Goal::Time => {
+				if out_of_time {
+					GoalStatus::Won
+				} else {
+					GoalStatus::InProgress
+				}
 			}
-		}
-		Goal::Bees(n) => {
-			if stats.killed_bees >= n {
-				cmds.run_system_cached_with(end_level, GameResult::Win);
-			} else if stats.time >= level.duration {
-				cmds.run_system_cached_with(end_level, GameResult::TimedOut);
+			Goal::Bees(n) => {
+				if stats.killed_bees >= *n {
+					GoalStatus::Won
+				} else if out_of_time {
+					GoalStatus::Failed(GameResult::TimedOut)
+				} else {
+					GoalStatus::InProgress
+				}
 			}
-		}
-		Goal::MaxMissed(n) => {
-			if stats.missed_bees >= n {
-				cmds.run_system_cached_with(end_level, GameResult::MissedTooMany);
-			} else if stats.time >= level.duration {
-				cmds.run_system_cached_with(end_level, GameResult::Win);
+			Goal::MaxMissed(n) => {
+				if stats.missed_bees >= *n {
+					GoalStatus::Failed(GameResult::MissedTooMany)
+				} else if out_of_time {
+					GoalStatus::Won
+				} else {
+					GoalStatus::Satisfied
+				}
+			}
+			Goal::KillRate(rate) => {
+				if !out_of_time {
+					GoalStatus::InProgress
+				} else if stats.kill_rate() >= *rate {
+					GoalStatus::Won
+				} else {
+					GoalStatus::Failed(GameResult::KillRateTooLow)
+				}
+			}
+			Goal::Lives(n) => {
+				if stats.lives_lost >= *n {
+					GoalStatus::Failed(GameResult::OutOfLives)
+				} else if out_of_time {
+					GoalStatus::Won
+				} else {
+					GoalStatus::Satisfied
+				}
+			}
+			Goal::Score(n) => {
+				if stats.score >= *n {
+					GoalStatus::Won
+				} else if out_of_time {
+					GoalStatus::Failed(GameResult::TimedOut)
+				} else {
+					GoalStatus::InProgress
+				}
+			}
+			Goal::All(goals) => {
+				let mut won = false;
+				let mut achieved = true;
+				for status in goals.iter().map(|goal| goal.status(stats, duration)) {
+					match status {
+						GoalStatus::Failed(_) => return status,
+						GoalStatus::Won => won = true,
+						GoalStatus::Satisfied => {}
+						GoalStatus::InProgress => achieved = false,
+					}
+				}
+				match (achieved, won) {
+					(true, true) => GoalStatus::Won,
+					(true, false) => GoalStatus::Satisfied,
+					(false, _) => GoalStatus::InProgress,
+				}
+			}
+			Goal::Any(goals) => {
+				let mut first_failure = None;
+				let mut satisfied = false;
+				let mut failed = true;
+				for status in goals.iter().map(|goal| goal.status(stats, duration)) {
+					match status {
+						GoalStatus::Won => return status,
+						GoalStatus::Failed(_) => {
+							first_failure.get_or_insert(status);
+						}
+						GoalStatus::Satisfied => {
+							satisfied = true;
+							failed = false;
+						}
+						GoalStatus::InProgress => failed = false,
+					}
+				}
+				match first_failure {
+					Some(failure) if failed => failure,
+					_ if satisfied => GoalStatus::Satisfied,
+					_ => GoalStatus::InProgress,
+				}
 			}
 		}
 	}
+
+	/// All non-composite goals within this one, in order.
+	pub fn leaves(&self) -> Vec<&Goal> {
+		match self {
+			Goal::All(goals) | Goal::Any(goals) => goals.iter().flat_map(Goal::leaves).collect(),
+			goal => vec![goal],
+		}
+	}
+
+	/// How many times the player can go out of bounds before the level is lost.
+	pub fn lives(&self) -> u32 {
+		self.leaves()
+			.into_iter()
+			.filter_map(|goal| match goal {
+				Goal::Lives(n) => Some(*n),
+				_ => None,
+			})
+			.max()
+			.unwrap_or(1)
+	}
+
+	/// How the level ends with these `stats`, or `None` if it carries on.
+	pub fn result(&self, stats: &LevelStats, duration: Duration) -> Option<GameResult> {
+		let lives = self.lives();
+		if stats.lives_lost >= lives {
+			return Some(if lives > 1 {
+				GameResult::OutOfLives
+			} else {
+				stats.last_life_lost.unwrap_or(GameResult::OutOfBounds)
+			});
+		}
+		match self.status(stats, duration) {
+			GoalStatus::Won => Some(GameResult::Win),
+			GoalStatus::Failed(result) => Some(result),
+			// Every goal should be decided by the end of the level, but in case a
+			// composite goal is empty, the level still needs to end somehow.
+			GoalStatus::Satisfied if stats.time >= duration => Some(GameResult::Win),
+			GoalStatus::InProgress if stats.time >= duration => Some(GameResult::TimedOut),
+			GoalStatus::Satisfied | GoalStatus::InProgress => None,
+		}
+	}
+
+	/// Human-readable progress towards a non-composite goal, for display in the HUD.
+	pub fn progress_text(&self, stats: &LevelStats, duration: Duration) -> String {
+		match self {
+			Goal::Time => format!("Survive {}s", duration.as_secs()),
+			Goal::Bees(n) => format!("Kill {}/{n} bees", stats.killed_bees),
+			Goal::MaxMissed(n) => format!("Miss fewer than {n} ({})", stats.missed_bees),
+			Goal::KillRate(rate) => format!("Kill {:.1}/{rate:.1} bees/s", stats.kill_rate()),
+			Goal::Lives(n) => format!("Lives: {}/{n}", n.saturating_sub(stats.lives_lost)),
+			Goal::Score(n) => format!("Score {}/{n}", stats.score),
+			Goal::All(_) => "Complete all of:".to_owned(),
+			Goal::Any(_) => "Complete any of:".to_owned(),
+		}
+	}
+}
+
+pub fn check_goal(mut cmds: Commands, level: Res<Level>, stats: Res<LevelStats>) {
+	if let Some(result) = level.goal.result(&stats, level.duration) {
+		cmds.run_system_cached_with(end_level, result);
+	}
 }
@@ -0,0 +1,82 @@
+use crate::PlayState;
+use crate::gameplay_assets::GameplayAssets;
+use crate::levels::Level;
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// How long the countdown before each level lasts.
+pub const COUNTDOWN_DURATION: Duration = Duration::from_secs(3);
+
+pub struct CountdownPlugin;
+
+impl Plugin for CountdownPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(OnEnter(PlayState::Countdown), start_countdown)
+			.add_systems(
+				Update,
+				tick_countdown.run_if(in_state(PlayState::Countdown)),
+			)
+			.add_systems(OnExit(PlayState::Countdown), remove_countdown_timer);
+	}
+}
+
+#[derive(Resource, Debug, Clone, Deref, DerefMut)]
+pub struct CountdownTimer(Timer);
+
+#[derive(Component, Debug, Copy, Clone)]
+pub struct CountdownDisplay;
+
+pub fn start_countdown(mut cmds: Commands, assets: Res<GameplayAssets>, level: Res<Level>) {
+	cmds.insert_resource(CountdownTimer(Timer::new(
+		COUNTDOWN_DURATION,
+		TimerMode::Once,
+	)));
+	cmds.spawn((
+		Node {
+			flex_direction: FlexDirection::Column,
+			align_items: AlignItems::Center,
+			position_type: PositionType::Absolute,
+			align_self: AlignSelf::Center,
+			justify_self: JustifySelf::Center,
+			..default()
+		},
+		StateScoped(PlayState::Countdown),
+	))
+	.with_children(|cmds| {
+		cmds.spawn((
+			Text(level.name.clone()),
+			TextFont {
+				font: assets.font.clone(),
+				font_size: 48.0,
+				..default()
+			},
+		));
+		cmds.spawn((
+			CountdownDisplay,
+			Text(COUNTDOWN_DURATION.as_secs().to_string()),
+			TextFont {
+				font: assets.font.clone(),
+				font_size: 128.0,
+				..default()
+			},
+		));
+	});
+}
+
+pub fn tick_countdown(
+	mut timer: ResMut<CountdownTimer>,
+	mut display: Single<&mut Text, With<CountdownDisplay>>,
+	mut next_state: ResMut<NextState<PlayState>>,
+	t: Res<Time>,
+) {
+	timer.tick(t.delta());
+	if timer.finished() {
+		next_state.set(PlayState::Running);
+	} else {
+		display.0 = timer.remaining_secs().ceil().to_string();
+	}
+}
+
+pub fn remove_countdown_timer(mut cmds: Commands) {
+	cmds.remove_resource::<CountdownTimer>();
+}
@@ -1,19 +1,31 @@
 #![feature(iter_map_windows)]
 
-use crate::levels::LevelList;
 use bevy::prelude::*;
 
 pub mod bees;
+pub mod bot;
+pub mod gameplay_assets;
 pub mod hud;
+pub mod interpolation;
 pub mod levels;
 pub mod loading;
 pub mod main_menu;
 pub mod map;
+pub mod medals;
+pub mod pause;
 pub mod player;
 pub mod portals;
+pub mod replay;
+pub mod rng;
 pub mod save;
+pub mod settings;
+pub mod sim;
+pub mod spatial;
+pub mod splash;
 pub mod stats;
 
+pub use splash::SplashPlugin;
+
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, States)]
 pub enum GameState {
 	#[default]
@@ -21,29 +33,23 @@ pub enum GameState {
 	MainMenu,
 	LevelSelect,
 	Loading,
+	LoadFailed,
 	Playing,
 	LevelEnd,
 }
 
-pub struct SplashPlugin;
-
-impl Plugin for SplashPlugin {
-	fn build(&self, app: &mut App) {
-		app.add_systems(OnEnter(GameState::Splash), show_splash)
-			.add_systems(
-				PostUpdate,
-				goto_main_menu
-					.run_if(in_state(GameState::Splash))
-					.run_if(resource_exists::<LevelList>),
-			);
-	}
-}
-
-pub fn show_splash() {
-	// TODO: Show splash screen
+/// Whether gameplay is currently advancing. Only exists while in [`GameState::Playing`].
+#[derive(SubStates, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[source(GameState = GameState::Playing)]
+pub enum PlayState {
+	/// Counting down before gameplay starts, with the level visible but frozen.
+	#[default]
+	Countdown,
+	Running,
+	Paused,
 }
 
-pub fn goto_main_menu(mut next_state: ResMut<NextState<GameState>>) {
-	info!("Going to main menu");
-	next_state.set(GameState::MainMenu);
+/// The argument following `name` on the command line.
+pub fn arg_value(name: &str) -> Option<String> {
+	std::env::args().skip_while(|arg| arg != name).nth(1)
 }
@@ -1,7 +1,12 @@
 use crate::GameState;
+use crate::levels::MainMenuButton;
+use crate::main_menu::UiAssets;
+use bevy::asset::{LoadState, RecursiveDependencyLoadState, UntypedAssetId};
 use bevy::ecs::intern::{Interned, Interner};
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
+use indexmap::IndexMap;
+use std::time::Duration;
 
 static LOADING_TASK_INTERNER: Interner<str> = Interner::new();
 
@@ -10,7 +15,6 @@ pub struct LoadingPlugin;
 impl Plugin for LoadingPlugin {
 	fn build(&self, app: &mut App) {
 		app.init_resource::<LoadingTasks>()
-			.add_systems(OnEnter(GameState::Loading), show_loading_screen)
 			.add_systems(
 				Update,
 				check_loading_progress.run_if(in_state(GameState::Loading)),
@@ -19,31 +23,109 @@ impl Plugin for LoadingPlugin {
 	}
 }
 
+/// Shows the progress of [`LoadingTasks`] while loading, and why loading failed if it does.
+pub struct LoadingScreenPlugin;
+
+impl Plugin for LoadingScreenPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(OnEnter(GameState::Loading), show_loading_screen)
+			.add_systems(
+				Update,
+				update_loading_screen.run_if(in_state(GameState::Loading)),
+			)
+			.add_systems(OnEnter(GameState::LoadFailed), show_load_failed_screen);
+	}
+}
+
+/// Tasks are kept in the order they were started, so they can be listed consistently.
 #[derive(Resource, Default, Debug)]
-pub struct LoadingTasks(HashMap<LoadingTaskHandle, LoadingStatus>);
+pub struct LoadingTasks {
+	tasks: IndexMap<LoadingTaskHandle, LoadingStatus>,
+	timeouts: HashMap<LoadingTaskHandle, Timer>,
+}
 
 impl LoadingTasks {
 	pub fn start(&mut self, name: impl AsRef<str>) -> LoadingTaskHandle {
 		let name = LOADING_TASK_INTERNER.intern(name.as_ref());
 		info!("Loading {}", &*name);
 		let handle = LoadingTaskHandle(name);
-		self.0.insert(handle, LoadingStatus::Loading);
+		self.tasks.insert(handle, LoadingStatus::Loading(0.0));
+		self.timeouts.remove(&handle);
 		handle
 	}
 
+	/// Start a task which fails if it is not finished within `timeout`.
+	pub fn start_with_timeout(
+		&mut self,
+		name: impl AsRef<str>,
+		timeout: Duration,
+	) -> LoadingTaskHandle {
+		let handle = self.start(name);
+		self.timeouts
+			.insert(handle, Timer::new(timeout, TimerMode::Once));
+		handle
+	}
+
+	/// Report how far along a task is, from 0 to 1.
+	pub fn set_progress(&mut self, handle: LoadingTaskHandle, progress: f32) {
+		let Some(status) = self.tasks.get_mut(&handle) else {
+			error!("No loading task {:?}", &*handle.0);
+			return;
+		};
+		if let LoadingStatus::Loading(_) = status {
+			*status = LoadingStatus::Loading(progress.clamp(0.0, 1.0));
+		}
+	}
+
 	pub fn finish(&mut self, handle: LoadingTaskHandle) {
-		let Some(status) = self.0.get_mut(&handle) else {
+		let Some(status) = self.tasks.get_mut(&handle) else {
 			error!("No loading task {:?}", &*handle.0);
 			return;
 		};
 		*status = LoadingStatus::Done;
 	}
 
+	pub fn fail(&mut self, handle: LoadingTaskHandle, reason: impl Into<String>) {
+		let Some(status) = self.tasks.get_mut(&handle) else {
+			error!("No loading task {:?}", &*handle.0);
+			return;
+		};
+		let reason = reason.into();
+		error!("Failed to load {}: {reason}", &*handle.0);
+		*status = LoadingStatus::Failed(reason);
+	}
+
+	/// Fails any unfinished tasks which have run out of time.
+	pub fn tick_timeouts(&mut self, delta: Duration) {
+		let mut timed_out = Vec::new();
+		for (handle, timer) in &mut self.timeouts {
+			if timer.tick(delta).just_finished()
+				&& matches!(self.tasks.get(handle), Some(LoadingStatus::Loading(_)))
+			{
+				timed_out.push((*handle, timer.duration()));
+			}
+		}
+		for (handle, timeout) in timed_out {
+			self.fail(
+				handle,
+				format!("Timed out after {:.1}s", timeout.as_secs_f32()),
+			);
+		}
+	}
+
+	/// The first task to have failed, if any, and the reason it failed.
+	pub fn failure(&self) -> Option<(LoadingTaskHandle, &str)> {
+		self.tasks.iter().find_map(|(handle, status)| match status {
+			LoadingStatus::Failed(reason) => Some((*handle, reason.as_str())),
+			_ => None,
+		})
+	}
+
 	/// Find a task by name. This is a lazy way to get a handle to a task, which doesn't
 	/// scale to larger projects, as it is effectively a "stringly-typed" API.
 	pub fn find(&self, name: impl AsRef<str>) -> Option<LoadingTaskHandle> {
 		let name = LOADING_TASK_INTERNER.intern(name.as_ref());
-		self.0.iter().find_map(|(handle, _)| {
+		self.tasks.iter().find_map(|(handle, _)| {
 			if handle.0 == name {
 				Some(*handle)
 			} else {
@@ -51,37 +133,220 @@ impl LoadingTasks {
 			}
 		})
 	}
+
+	pub fn iter(&self) -> impl Iterator<Item = (LoadingTaskHandle, &LoadingStatus)> {
+		self.tasks.iter().map(|(handle, status)| (*handle, status))
+	}
+
+	/// Average progress of all tasks, from 0 to 1.
+	pub fn progress(&self) -> f32 {
+		if self.tasks.is_empty() {
+			return 0.0;
+		}
+		self.tasks
+			.values()
+			.map(LoadingStatus::progress)
+			.sum::<f32>()
+			/ self.tasks.len() as f32
+	}
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum LoadingStatus {
-	Loading,
+	/// Still loading, with progress from 0 to 1.
+	Loading(f32),
 	Done,
+	/// Loading failed, for the given reason.
+	Failed(String),
 }
 
 impl LoadingStatus {
 	pub fn done(&self) -> bool {
 		matches!(self, LoadingStatus::Done)
 	}
+
+	pub fn progress(&self) -> f32 {
+		match self {
+			LoadingStatus::Loading(progress) => *progress,
+			LoadingStatus::Done => 1.0,
+			LoadingStatus::Failed(_) => 0.0,
+		}
+	}
+}
+
+/// Updates a task's progress based on how many of `handles` have loaded,
+/// finishing it once they all have, or failing it if any of them fail.
+pub fn report_asset_progress(
+	tasks: &mut LoadingTasks,
+	task: LoadingTaskHandle,
+	server: &AssetServer,
+	handles: &[UntypedHandle],
+) {
+	if let Some(reason) = handles
+		.iter()
+		.find_map(|handle| asset_load_failure(server, handle))
+	{
+		tasks.fail(task, reason);
+		return;
+	}
+	let loaded = handles
+		.iter()
+		.filter(|handle| server.is_loaded_with_dependencies(handle.id()))
+		.count();
+	if loaded == handles.len() {
+		tasks.finish(task);
+	} else {
+		tasks.set_progress(task, loaded as f32 / handles.len() as f32);
+	}
+}
+
+/// Describes why an asset failed to load, if it or any of its dependencies did.
+pub fn asset_load_failure(server: &AssetServer, id: impl Into<UntypedAssetId>) -> Option<String> {
+	let id = id.into();
+	if let Some(LoadState::Failed(err)) = server.get_load_state(id) {
+		return Some(err.to_string());
+	}
+	if let Some(RecursiveDependencyLoadState::Failed(err)) =
+		server.get_recursive_dependency_load_state(id)
+	{
+		return Some(err.to_string());
+	}
+	None
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 #[must_use]
 pub struct LoadingTaskHandle(Interned<str>);
 
-pub fn show_loading_screen() {
-	// TODO: Show loading screen
+impl LoadingTaskHandle {
+	pub fn name(&self) -> &'static str {
+		self.0.0
+	}
+}
+
+pub fn show_loading_screen(mut cmds: Commands, assets: Res<UiAssets>) {
 	info!("Showing loading screen");
+	cmds.spawn((Camera2d, StateScoped::<GameState>(GameState::Loading)));
+	let font = TextFont {
+		font: assets.font.clone(),
+		font_size: 24.0,
+		..default()
+	};
+
+	cmds.spawn((
+		Node {
+			flex_direction: FlexDirection::Column,
+			align_self: AlignSelf::Center,
+			justify_self: JustifySelf::Center,
+			align_items: AlignItems::Center,
+			padding: UiRect::all(Val::Px(20.0)),
+			row_gap: Val::Px(10.0),
+			..default()
+		},
+		BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.7)),
+		StateScoped::<GameState>(GameState::Loading),
+	))
+	.with_children(|cmds| {
+		cmds.spawn((
+			Text("Loading...".into()),
+			TextFont {
+				font_size: 32.0,
+				..font.clone()
+			},
+		));
+		cmds.spawn((
+			LoadingTaskList,
+			Node {
+				flex_direction: FlexDirection::Column,
+				min_width: Val::Px(300.0),
+				..default()
+			},
+		));
+		cmds.spawn((
+			Node {
+				width: Val::Px(300.0),
+				height: Val::Px(16.0),
+				..default()
+			},
+			BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+		))
+		.with_child((
+			LoadingProgressBar,
+			Node {
+				width: Val::Percent(0.0),
+				height: Val::Percent(100.0),
+				..default()
+			},
+			BackgroundColor(Color::srgb(0.0, 0.6, 0.8)),
+		));
+	});
 }
 
-pub fn check_loading_progress(
+/// Container for one [`LoadingTaskRow`] per task.
+#[derive(Component, Debug, Copy, Clone)]
+#[require(Node)]
+pub struct LoadingTaskList;
+
+#[derive(Component, Debug, Copy, Clone)]
+#[require(Text)]
+pub struct LoadingTaskRow(pub LoadingTaskHandle);
+
+#[derive(Component, Debug, Copy, Clone)]
+#[require(Node)]
+pub struct LoadingProgressBar;
+
+pub fn update_loading_screen(
+	mut cmds: Commands,
 	tasks: Res<LoadingTasks>,
+	assets: Res<UiAssets>,
+	list: Single<Entity, With<LoadingTaskList>>,
+	mut rows: Query<(&LoadingTaskRow, &mut Text)>,
+	mut bar: Single<&mut Node, With<LoadingProgressBar>>,
+) {
+	bar.width = Val::Percent(tasks.progress() * 100.0);
+	for (handle, status) in tasks.iter() {
+		let text = match status {
+			LoadingStatus::Loading(progress) => {
+				format!("{}: {:.0}%", handle.name(), progress * 100.0)
+			}
+			LoadingStatus::Done => format!("{}: Done", handle.name()),
+			LoadingStatus::Failed(_) => format!("{}: Failed", handle.name()),
+		};
+		if let Some((_, mut row_text)) = rows.iter_mut().find(|(row, _)| row.0 == handle) {
+			row_text.0 = text;
+		} else {
+			cmds.entity(*list).with_child((
+				LoadingTaskRow(handle),
+				Text(text),
+				TextFont {
+					font: assets.font.clone(),
+					font_size: 24.0,
+					..default()
+				},
+			));
+		}
+	}
+}
+
+pub fn check_loading_progress(
+	mut cmds: Commands,
+	mut tasks: ResMut<LoadingTasks>,
 	mut next_state: ResMut<NextState<GameState>>,
+	t: Res<Time>,
 ) {
-	if tasks.0.is_empty() {
+	tasks.tick_timeouts(t.delta());
+	if let Some((handle, reason)) = tasks.failure() {
+		cmds.insert_resource(LoadingFailure {
+			task: handle.name().to_owned(),
+			reason: reason.to_owned(),
+		});
+		next_state.set(GameState::LoadFailed);
+		return;
+	}
+	if tasks.tasks.is_empty() {
 		return;
 	}
-	for task in tasks.0.values() {
+	for task in tasks.tasks.values() {
 		if !task.done() {
 			return;
 		}
@@ -91,5 +356,101 @@ pub fn check_loading_progress(
 }
 
 pub fn clear_loading_tasks(mut tasks: ResMut<LoadingTasks>) {
-	tasks.0.clear();
+	tasks.tasks.clear();
+	tasks.timeouts.clear();
+}
+
+/// Which loading task failed, and why, for display on the error screen.
+#[derive(Resource, Debug, Clone)]
+pub struct LoadingFailure {
+	pub task: String,
+	pub reason: String,
+}
+
+#[derive(Component, Debug, Copy, Clone)]
+pub struct RetryLoadingButton;
+
+pub fn show_load_failed_screen(
+	mut cmds: Commands,
+	failure: Res<LoadingFailure>,
+	assets: Res<UiAssets>,
+) {
+	cmds.spawn((Camera2d, StateScoped::<GameState>(GameState::LoadFailed)));
+	let font = assets.font.clone();
+
+	cmds.spawn((
+		Node {
+			flex_direction: FlexDirection::Column,
+			align_items: AlignItems::Center,
+			position_type: PositionType::Absolute,
+			align_self: AlignSelf::Center,
+			justify_self: JustifySelf::Center,
+			max_width: Val::Percent(80.0),
+			padding: UiRect::all(Val::Px(20.0)),
+			row_gap: Val::Px(10.0),
+			..default()
+		},
+		BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.7)),
+		StateScoped::<GameState>(GameState::LoadFailed),
+	))
+	.with_children(|cmds| {
+		cmds.spawn((
+			Text(format!("Failed to load {}", failure.task)),
+			TextFont {
+				font: font.clone(),
+				font_size: 48.0,
+				..default()
+			},
+			TextColor(Color::srgb(0.8, 0.2, 0.2)),
+		));
+		cmds.spawn((
+			Text(failure.reason.clone()),
+			TextFont {
+				font: font.clone(),
+				font_size: 20.0,
+				..default()
+			},
+		));
+	});
+
+	let font = TextFont {
+		font,
+		font_size: 32.0,
+		..default()
+	};
+
+	// Buttons
+	cmds.spawn((
+		Node {
+			position_type: PositionType::Absolute,
+			bottom: Val::Px(60.0),
+			justify_self: JustifySelf::Center,
+			..default()
+		},
+		StateScoped::<GameState>(GameState::LoadFailed),
+	))
+	.with_children(|cmds| {
+		cmds.spawn((
+			MainMenuButton,
+			Button,
+			Node {
+				margin: UiRect::all(Val::Px(10.0)),
+				padding: UiRect::all(Val::Px(10.0)),
+				..default()
+			},
+			BackgroundColor(Color::srgb(0.4, 0.2, 0.2)),
+		))
+		.with_child((Text("Main Menu".into()), font.clone()));
+		cmds.spawn((
+			RetryLoadingButton,
+			Button,
+			Node {
+				margin: UiRect::all(Val::Px(10.0)),
+				padding: UiRect::all(Val::Px(10.0)),
+				..default()
+			},
+			BackgroundColor(Color::srgb(0.0, 0.3, 0.4)),
+		))
+		.with_child((Text("Retry".into()), font));
+	});
 }
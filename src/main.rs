@@ -1,32 +1,69 @@
 use bevy::prelude::*;
+use bevy::window::WindowResolution;
 use bevy_enhanced_input::EnhancedInputPlugin;
 pub use jeremy_bearimy::*;
 
 fn main() {
-	App::new()
-		.add_plugins((
-			DefaultPlugins.set(WindowPlugin {
-				primary_window: Some(Window {
-					title: "Jeremy Bearimy".into(),
-					resolution: (960.0, 540.0).into(),
-					..default()
-				}),
+	let mut app = App::new();
+	if let Some(path) = arg_value("--replay") {
+		match replay::Replay::load(&path) {
+			Ok(replay) => {
+				app.insert_resource(replay::PendingReplay(replay));
+			}
+			Err(e) => eprintln!("Failed to load replay {path:?}: {e}"),
+		}
+	}
+	app.add_plugins((
+		DefaultPlugins.set(WindowPlugin {
+			primary_window: Some(Window {
+				title: "Jeremy Bearimy".into(),
+				resolution: WindowResolution::new(960.0, 540.0),
 				..default()
 			}),
-			EnhancedInputPlugin,
-			bees::BeesPlugin,
-			hud::HudPlugin,
-			levels::LevelsPlugin,
-			loading::LoadingPlugin,
-			main_menu::MainMenuPlugin,
-			map::MapPlugin,
-			player::PlayerPlugin,
-			portals::PortalsPlugin,
-			save::SavePlugin,
-			stats::StatsPlugin,
-			SplashPlugin,
-		))
-		.init_state::<GameState>()
-		.enable_state_scoped_entities::<GameState>()
-		.run();
+			..default()
+		}),
+		EnhancedInputPlugin,
+		interpolation::InterpolationPlugin,
+		gameplay_assets::GameplayAssetsPlugin,
+		loading::LoadingScreenPlugin,
+		player::PlayerAssetsPlugin,
+		rng::RngPlugin,
+		replay::ReplayPlugin,
+	))
+	.add_plugins((
+		bees::BeesPlugin,
+		hud::HudPlugin,
+		levels::LevelsPlugin,
+		loading::LoadingPlugin,
+		main_menu::MainMenuPlugin,
+		map::MapPlugin::default(),
+		medals::MedalsPlugin,
+		pause::PausePlugin,
+		player::PlayerPlugin,
+		portals::PortalsPlugin,
+		save::SavePlugin,
+		settings::SettingsPlugin,
+		stats::StatsPlugin,
+		SplashPlugin,
+	))
+	.insert_resource(rng::SeedOverride(seed_arg()))
+	.init_state::<GameState>()
+	.enable_state_scoped_entities::<GameState>()
+	.add_sub_state::<PlayState>()
+	.enable_state_scoped_entities::<PlayState>()
+	.add_sub_state::<pause::PauseMenu>()
+	.enable_state_scoped_entities::<pause::PauseMenu>()
+	.run();
+}
+
+/// Parses `--seed <seed>` from the command line.
+fn seed_arg() -> Option<u64> {
+	let seed = arg_value("--seed")?;
+	match seed.parse() {
+		Ok(seed) => Some(seed),
+		Err(e) => {
+			eprintln!("Invalid seed {seed:?}: {e}");
+			None
+		}
+	}
 }
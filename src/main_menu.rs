@@ -1,5 +1,6 @@
 use crate::GameState;
 use crate::levels::LevelList;
+use crate::loading::{LoadingTaskHandle, LoadingTasks, report_asset_progress};
 use crate::save::SaveData;
 use crate::stats::RunStats;
 use bevy::color::palettes::tailwind::{GRAY_400, GRAY_500, GRAY_600, GRAY_800, GRAY_900};
@@ -23,15 +24,48 @@ impl Plugin for MainMenuPlugin {
 	}
 }
 
+/// Assets used by menus and other UI outside of levels, loaded during the splash screen.
+#[derive(Resource, Debug, Clone)]
+pub struct UiAssets {
+	loading_task_handle: LoadingTaskHandle,
+	pub font: Handle<Font>,
+}
+
+impl UiAssets {
+	pub fn load(
+		mut cmds: Commands,
+		mut loading_tasks: ResMut<LoadingTasks>,
+		server: Res<AssetServer>,
+	) {
+		cmds.insert_resource(Self {
+			loading_task_handle: loading_tasks.start("UI Assets"),
+			font: server.load("ShareTechMono-Regular.ttf"),
+		});
+	}
+
+	pub fn check_progress(
+		assets: Res<UiAssets>,
+		server: Res<AssetServer>,
+		mut loading_tasks: ResMut<LoadingTasks>,
+	) {
+		report_asset_progress(
+			&mut loading_tasks,
+			assets.loading_task_handle,
+			&server,
+			&[assets.font.clone().untyped()],
+		);
+	}
+}
+
 pub fn show_main_menu(
 	mut cmds: Commands,
-	server: Res<AssetServer>,
+	assets: Res<UiAssets>,
 	save: Res<Persistent<SaveData>>,
 ) {
 	info!("Showing main menu");
 	cmds.spawn((Camera2d, StateScoped::<GameState>(GameState::MainMenu)));
 	let font = TextFont {
-		font: server.load::<Font>("ShareTechMono-Regular.ttf"),
+		font: assets.font.clone(),
 		font_size: 24.0,
 		..default()
 	};
@@ -111,7 +145,7 @@ pub fn handle_play_btn(
 		info!("Loading first level");
 		level_list.1 = 0;
 		cmds.insert_resource(level_list[0].clone());
-		cmds.insert_resource(RunStats::default());
+		cmds.insert_resource(RunStats::new());
 	}
 }
 
@@ -2,7 +2,9 @@ use crate::GameState;
 use bevy::prelude::*;
 use bevy_persistent::Persistent;
 use crate::levels::{Level, LevelList};
+use crate::main_menu::UiAssets;
 use crate::save::SaveData;
+use crate::stats::RunStats;
 
 pub struct LevelSelectPlugin;
 
@@ -23,12 +25,12 @@ pub fn show_level_select(
 	mut cmds: Commands,
 	level_list: Res<LevelList>,
 	save: Res<Persistent<SaveData>>,
-	server: Res<AssetServer>
+	assets: Res<UiAssets>,
 ) {
 	info!("Showing level select screen");
 	cmds.spawn((Camera2d, StateScoped::<GameState>(GameState::LevelSelect)));
 	let font = TextFont {
-		font: server.load::<Font>("ShareTechMono-Regular.ttf"),
+		font: assets.font.clone(),
 		font_size: 24.0,
 		..default()
 	};
@@ -69,12 +71,34 @@ pub fn show_level_select(
 				LevelSelectionButton(i),
 				Button,
 				Node {
+					flex_direction: FlexDirection::Column,
+					align_items: AlignItems::Center,
 					margin: UiRect::all(Val::Px(10.0)),
 					padding: UiRect::all(Val::Px(10.0)),
 					..default()
 				},
 				BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.7)),
-			)).with_child((Text(level.name.clone()), font.clone()));
+			)).with_children(|cmds| {
+				cmds.spawn((Text(level.name.clone()), font.clone()));
+				let Some(medals) = level.medals else {
+					return;
+				};
+				let best = save.personal_bests.get(&level.name).and_then(|best| best.medal);
+				let small_font = TextFont {
+					font_size: 16.0,
+					..font.clone()
+				};
+				if let Some(medal) = best {
+					cmds.spawn((
+						Text(format!("{medal}")),
+						small_font.clone(),
+						TextColor(medal.color()),
+					));
+				}
+				if let Some(next) = medals.next_goal(best) {
+					cmds.spawn((Text(format!("Next - {next}")), small_font));
+				}
+			});
 		}
 	});
 	
@@ -86,13 +110,15 @@ pub struct LevelSelectionButton(pub usize);
 pub fn handle_level_selection_btn(
 	mut cmds: Commands,
 	btns: Query<(&Interaction, &LevelSelectionButton)>,
-	level_list: Res<LevelList>,
+	mut level_list: ResMut<LevelList>,
 ) {
 	for btn in &btns {
 		if *btn.0 == Interaction::Pressed {
 			info!("Loading level {}", level_list.0[**btn.1].name);
+			level_list.1 = **btn.1;
 			cmds.remove_resource::<Level>();
 			cmds.insert_resource(level_list.0[**btn.1].clone());
+			cmds.insert_resource(RunStats::new());
 		}
 	}
 }
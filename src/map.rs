@@ -1,5 +1,6 @@
-use crate::GameState;
+use crate::interpolation::InterpolatedTranslation;
 use crate::levels::Level;
+use crate::{GameState, PlayState};
 use bevy::asset::io::Reader;
 use bevy::asset::{AssetLoader, AssetPath, LoadContext, ReflectAsset, ron};
 use bevy::color::palettes::basic::{BLUE, GREEN, WHITE, YELLOW};
@@ -12,24 +13,40 @@ use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 
-pub struct MapPlugin;
+pub struct MapPlugin {
+	/// Apps which don't draw anything have nothing to load map backgrounds with.
+	pub load_backgrounds: bool,
+}
+
+impl Default for MapPlugin {
+	fn default() -> Self {
+		Self {
+			load_backgrounds: true,
+		}
+	}
+}
 
 impl Plugin for MapPlugin {
 	fn build(&self, app: &mut App) {
 		app.init_asset::<Map>()
 			.register_asset_reflect::<Map>()
+			.register_type::<TimelinePosition>()
 			.add_systems(PreUpdate, insert_loaded_map)
 			.add_systems(
 				Update,
-				(
-					tick_timeline_positions,
-					move_timeline_items,
-					dbg_draw_curve.run_if(input_toggle_active(false, KeyCode::KeyC)),
-				)
+				dbg_draw_curve
+					.run_if(input_toggle_active(false, KeyCode::KeyC))
 					.run_if(in_state(GameState::Playing)),
+			)
+			.add_systems(
+				FixedUpdate,
+				(tick_timeline_positions, move_timeline_items)
+					.chain()
+					.run_if(in_state(PlayState::Running)),
 			);
 		let loader = MapLoader {
 			registry: app.world().resource::<AppTypeRegistry>().0.clone(),
+			load_background: self.load_backgrounds,
 		};
 		app.register_asset_loader(loader);
 	}
@@ -322,6 +339,7 @@ impl Default for Map {
 
 pub struct MapLoader {
 	pub registry: TypeRegistryArc,
+	pub load_background: bool,
 }
 
 impl AssetLoader for MapLoader {
@@ -342,8 +360,9 @@ impl AssetLoader for MapLoader {
 		let mut deserializer = ron::Deserializer::from_bytes(&bytes)?;
 		let map = reflect_deserializer.deserialize(&mut deserializer)?;
 		let mut map: Map = Map::take_from_reflect(map)?;
-		let bg = load_context.load(&map.background);
-		map.bg_handle = bg;
+		if self.load_background {
+			map.bg_handle = load_context.load(&map.background);
+		}
 		map.sync()?;
 		Ok(map)
 	}
@@ -466,6 +485,8 @@ impl Display for AddPointError {
 }
 
 #[derive(Component, Debug, Copy, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[require(InterpolatedTranslation)]
 pub struct TimelinePosition {
 	/// The "time" value used to sample the map curve for position.
 	pub t: f32,
@@ -0,0 +1,175 @@
+use crate::GameState;
+use crate::levels::{Level, show_level_end_screen};
+use crate::save::{PersonalBest, SaveData};
+use crate::stats::{GameResult, LevelStats};
+use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::time::Duration;
+
+pub struct MedalsPlugin;
+
+impl Plugin for MedalsPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(
+			OnEnter(GameState::LevelEnd),
+			record_personal_best.before(show_level_end_screen),
+		);
+	}
+}
+
+#[derive(
+	Reflect, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[reflect(Debug, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Medal {
+	Bronze,
+	Silver,
+	Gold,
+}
+
+impl Medal {
+	pub fn next(self) -> Option<Self> {
+		match self {
+			Medal::Bronze => Some(Medal::Silver),
+			Medal::Silver => Some(Medal::Gold),
+			Medal::Gold => None,
+		}
+	}
+
+	pub fn color(self) -> Color {
+		match self {
+			Medal::Bronze => Color::srgb(0.8, 0.5, 0.2),
+			Medal::Silver => Color::srgb(0.75, 0.75, 0.75),
+			Medal::Gold => Color::srgb(1.0, 0.84, 0.0),
+		}
+	}
+}
+
+impl std::fmt::Display for Medal {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Medal::Bronze => write!(f, "Bronze"),
+			Medal::Silver => write!(f, "Silver"),
+			Medal::Gold => write!(f, "Gold"),
+		}
+	}
+}
+
+#[derive(Reflect, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Thresholds<T> {
+	pub bronze: T,
+	pub silver: T,
+	pub gold: T,
+}
+
+impl<T> Thresholds<T> {
+	pub fn get(&self, medal: Medal) -> &T {
+		match medal {
+			Medal::Bronze => &self.bronze,
+			Medal::Silver => &self.silver,
+			Medal::Gold => &self.gold,
+		}
+	}
+}
+
+/// What a level's medals are awarded for. Medals are only awarded for winning.
+#[derive(Reflect, Debug, Clone, Copy, Serialize, Deserialize)]
+#[reflect(Debug, Serialize, Deserialize)]
+pub enum MedalThresholds {
+	/// Win within this much time.
+	Time(Thresholds<Duration>),
+	/// Kill at least this many bees.
+	Kills(Thresholds<u32>),
+	/// Miss at most this many bees.
+	Missed(Thresholds<u32>),
+}
+
+impl MedalThresholds {
+	pub fn meets(&self, medal: Medal, stats: &LevelStats) -> bool {
+		match self {
+			MedalThresholds::Time(t) => stats.time <= *t.get(medal),
+			MedalThresholds::Kills(t) => stats.killed_bees >= *t.get(medal),
+			MedalThresholds::Missed(t) => stats.missed_bees <= *t.get(medal),
+		}
+	}
+
+	pub fn medal(&self, stats: &LevelStats) -> Option<Medal> {
+		if stats.result != Some(GameResult::Win) {
+			return None;
+		}
+		[Medal::Gold, Medal::Silver, Medal::Bronze]
+			.into_iter()
+			.find(|medal| self.meets(*medal, stats))
+	}
+
+	/// Describes what is needed to earn `medal`, e.g. "kill 500 bees".
+	pub fn requirement(&self, medal: Medal) -> String {
+		match self {
+			MedalThresholds::Time(t) => format!("win within {:.1}s", t.get(medal).as_secs_f32()),
+			MedalThresholds::Kills(t) => format!("kill {} bees", t.get(medal)),
+			MedalThresholds::Missed(t) => format!("miss at most {} bees", t.get(medal)),
+		}
+	}
+
+	/// The next medal after `current` and what is needed to earn it, if there is one.
+	pub fn next_goal(&self, current: Option<Medal>) -> Option<String> {
+		let next = match current {
+			Some(medal) => medal.next()?,
+			None => Medal::Bronze,
+		};
+		Some(format!("{next}: {}", self.requirement(next)))
+	}
+
+	/// Compares two attempts by whatever these thresholds measure.
+	pub fn compare(&self, a: &LevelStats, b: &LevelStats) -> Ordering {
+		match self {
+			MedalThresholds::Time(_) => b.time.cmp(&a.time),
+			MedalThresholds::Kills(_) => a.killed_bees.cmp(&b.killed_bees),
+			MedalThresholds::Missed(_) => b.missed_bees.cmp(&a.missed_bees),
+		}
+	}
+}
+
+/// Whether `new` should replace `old` as the personal best for `level`.
+pub fn is_better(level: &Level, new: &LevelStats, old: &LevelStats) -> bool {
+	let won = |stats: &LevelStats| stats.result == Some(GameResult::Win);
+	let by_result = won(new).cmp(&won(old));
+	let by_medal_criteria = level
+		.medals
+		.map(|medals| medals.compare(new, old))
+		.unwrap_or(Ordering::Equal);
+	by_result
+		.then(by_medal_criteria)
+		.then(new.killed_bees.cmp(&old.killed_bees))
+		.then(old.time.cmp(&new.time))
+		.is_gt()
+}
+
+pub fn record_personal_best(
+	level: Res<Level>,
+	stats: Res<LevelStats>,
+	save: Option<ResMut<Persistent<SaveData>>>,
+) {
+	let Some(mut save) = save else {
+		return;
+	};
+	let medal = level.medals.and_then(|medals| medals.medal(&stats));
+	if let Err(e) = save.update(|save| {
+		let best = save
+			.personal_bests
+			.entry(level.name.clone())
+			.or_insert_with(|| PersonalBest {
+				medal: None,
+				stats: stats.clone(),
+			});
+		best.medal = best.medal.max(medal);
+		if is_better(&level, &stats, &best.stats) {
+			info!("New personal best for {}", level.name);
+			best.stats = stats.clone();
+		}
+	}) {
+		error!("Failed to update save data: {e}");
+	}
+}
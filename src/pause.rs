@@ -0,0 +1,303 @@
+use crate::gameplay_assets::GameplayAssets;
+use crate::levels::{Level, MainMenuButton, despawn_level_entities};
+use crate::map::Map;
+use crate::settings::Settings;
+use crate::{GameState, PlayState};
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+use bevy_persistent::Persistent;
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(
+			Update,
+			(
+				toggle_pause.run_if(in_state(GameState::Playing)),
+				pause_on_focus_loss.run_if(in_state(PlayState::Running)),
+				(handle_resume_btn, handle_restart_btn, handle_settings_btn)
+					.run_if(in_state(PauseMenu::Main)),
+				(
+					handle_focus_loss_toggle,
+					handle_ghost_toggle,
+					handle_back_btn,
+				)
+					.run_if(in_state(PauseMenu::Settings)),
+			),
+		)
+		.add_systems(OnEnter(PlayState::Paused), pause_time)
+		.add_systems(OnExit(PlayState::Paused), unpause_time)
+		.add_systems(OnEnter(PauseMenu::Main), show_pause_menu)
+		.add_systems(OnEnter(PauseMenu::Settings), show_settings_menu);
+	}
+}
+
+/// Which page of the pause menu is showing. Only exists while paused.
+#[derive(SubStates, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[source(PlayState = PlayState::Paused)]
+pub enum PauseMenu {
+	#[default]
+	Main,
+	Settings,
+}
+
+pub fn toggle_pause(
+	keys: Res<ButtonInput<KeyCode>>,
+	gamepads: Query<&Gamepad>,
+	state: Res<State<PlayState>>,
+	mut next_state: ResMut<NextState<PlayState>>,
+) {
+	if keys.just_pressed(KeyCode::Escape)
+		|| gamepads
+			.iter()
+			.any(|pad| pad.just_pressed(GamepadButton::Start))
+	{
+		match state.get() {
+			PlayState::Countdown => {}
+			PlayState::Running => next_state.set(PlayState::Paused),
+			PlayState::Paused => next_state.set(PlayState::Running),
+		}
+	}
+}
+
+pub fn pause_on_focus_loss(
+	mut focus_events: EventReader<WindowFocused>,
+	settings: Option<Res<Persistent<Settings>>>,
+	mut next_state: ResMut<NextState<PlayState>>,
+) {
+	let lost_focus = focus_events.read().any(|event| !event.focused);
+	if lost_focus && settings.is_none_or(|settings| settings.pause_on_focus_loss) {
+		next_state.set(PlayState::Paused);
+	}
+}
+
+/// Stops virtual time so anything driven by [`Time`] freezes, including [`LevelStats::time`](crate::stats::LevelStats::time).
+pub fn pause_time(mut time: ResMut<Time<Virtual>>) {
+	time.pause();
+}
+
+pub fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+	time.unpause();
+}
+
+fn menu_root() -> impl Bundle {
+	Node {
+		flex_direction: FlexDirection::Column,
+		align_items: AlignItems::Stretch,
+		position_type: PositionType::Absolute,
+		align_self: AlignSelf::Center,
+		justify_self: JustifySelf::Center,
+		padding: UiRect::all(Val::Px(20.0)),
+		row_gap: Val::Px(10.0),
+		..default()
+	}
+}
+
+fn menu_btn(color: Color) -> impl Bundle {
+	(
+		Button,
+		Node {
+			justify_content: JustifyContent::Center,
+			padding: UiRect::all(Val::Px(10.0)),
+			..default()
+		},
+		BackgroundColor(color),
+	)
+}
+
+pub fn show_pause_menu(mut cmds: Commands, assets: Res<GameplayAssets>) {
+	let font = TextFont {
+		font: assets.font.clone(),
+		font_size: 32.0,
+		..default()
+	};
+	let btn_color = Color::srgb(0.0, 0.3, 0.4);
+
+	cmds.spawn((
+		menu_root(),
+		BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.7)),
+		StateScoped(PauseMenu::Main),
+	))
+	.with_children(|cmds| {
+		cmds.spawn((
+			Text("Paused".into()),
+			TextFont {
+				font_size: 48.0,
+				..font.clone()
+			},
+		));
+		cmds.spawn((ResumeButton, menu_btn(btn_color)))
+			.with_child((Text("Resume".into()), font.clone()));
+		cmds.spawn((RestartLevelButton, menu_btn(btn_color)))
+			.with_child((Text("Restart Level".into()), font.clone()));
+		cmds.spawn((SettingsButton, menu_btn(btn_color)))
+			.with_child((Text("Settings".into()), font.clone()));
+		cmds.spawn((MainMenuButton, menu_btn(Color::srgb(0.4, 0.2, 0.2))))
+			.with_child((Text("Quit to Main Menu".into()), font));
+	});
+}
+
+pub fn show_settings_menu(
+	mut cmds: Commands,
+	assets: Res<GameplayAssets>,
+	settings: Option<Res<Persistent<Settings>>>,
+) {
+	let font = TextFont {
+		font: assets.font.clone(),
+		font_size: 32.0,
+		..default()
+	};
+	let btn_color = Color::srgb(0.0, 0.3, 0.4);
+
+	cmds.spawn((
+		menu_root(),
+		BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.7)),
+		StateScoped(PauseMenu::Settings),
+	))
+	.with_children(|cmds| {
+		cmds.spawn((
+			Text("Settings".into()),
+			TextFont {
+				font_size: 48.0,
+				..font.clone()
+			},
+		));
+		if let Some(settings) = settings {
+			cmds.spawn((FocusLossToggle, menu_btn(btn_color)))
+				.with_child((
+					Text(focus_loss_label(settings.pause_on_focus_loss)),
+					font.clone(),
+				));
+			cmds.spawn((GhostToggle, menu_btn(btn_color)))
+				.with_child((Text(ghost_label(settings.show_ghost)), font.clone()));
+		} else {
+			cmds.spawn((
+				Text("Settings could not be loaded".into()),
+				font.clone(),
+				TextColor(Color::srgb(0.8, 0.2, 0.2)),
+			));
+		}
+		cmds.spawn((BackButton, menu_btn(btn_color)))
+			.with_child((Text("Back".into()), font));
+	});
+}
+
+fn focus_loss_label(enabled: bool) -> String {
+	format!(
+		"Pause When Unfocused: {}",
+		if enabled { "On" } else { "Off" }
+	)
+}
+
+#[derive(Component, Debug, Copy, Clone)]
+pub struct ResumeButton;
+
+pub fn handle_resume_btn(
+	interaction: Single<&Interaction, With<ResumeButton>>,
+	mut next_state: ResMut<NextState<PlayState>>,
+) {
+	if **interaction == Interaction::Pressed {
+		next_state.set(PlayState::Running);
+	}
+}
+
+#[derive(Component, Debug, Copy, Clone)]
+pub struct RestartLevelButton;
+
+pub fn handle_restart_btn(
+	mut cmds: Commands,
+	level: Res<Level>,
+	interaction: Single<&Interaction, With<RestartLevelButton>>,
+) {
+	if **interaction == Interaction::Pressed {
+		info!("Restarting level {}", level.name);
+		cmds.run_system_cached(despawn_level_entities);
+		// Reinserting the level reloads it, which also resets the stats.
+		cmds.remove_resource::<Map>();
+		cmds.remove_resource::<Level>();
+		cmds.insert_resource(level.clone());
+	}
+}
+
+#[derive(Component, Debug, Copy, Clone)]
+pub struct SettingsButton;
+
+pub fn handle_settings_btn(
+	interaction: Single<&Interaction, With<SettingsButton>>,
+	mut next_state: ResMut<NextState<PauseMenu>>,
+) {
+	if **interaction == Interaction::Pressed {
+		next_state.set(PauseMenu::Settings);
+	}
+}
+
+#[derive(Component, Debug, Copy, Clone)]
+pub struct FocusLossToggle;
+
+pub fn handle_focus_loss_toggle(
+	toggle: Single<(Ref<Interaction>, &Children), With<FocusLossToggle>>,
+	mut texts: Query<&mut Text>,
+	settings: Option<ResMut<Persistent<Settings>>>,
+) {
+	let (interaction, children) = toggle.into_inner();
+	let Some(mut settings) = settings else {
+		return;
+	};
+	if !interaction.is_changed() || *interaction != Interaction::Pressed {
+		return;
+	}
+	let enabled = !settings.pause_on_focus_loss;
+	if let Err(e) = settings.update(|settings| settings.pause_on_focus_loss = enabled) {
+		error!("Failed to save settings: {e}");
+	}
+	let mut texts = texts.iter_many_mut(children);
+	while let Some(mut text) = texts.fetch_next() {
+		text.0 = focus_loss_label(settings.pause_on_focus_loss);
+	}
+}
+
+#[derive(Component, Debug, Copy, Clone)]
+pub struct GhostToggle;
+
+fn ghost_label(enabled: bool) -> String {
+	format!(
+		"Personal Best Ghost: {}",
+		if enabled { "On" } else { "Off" }
+	)
+}
+
+/// Takes effect the next time a level starts.
+pub fn handle_ghost_toggle(
+	toggle: Single<(Ref<Interaction>, &Children), With<GhostToggle>>,
+	mut texts: Query<&mut Text>,
+	settings: Option<ResMut<Persistent<Settings>>>,
+) {
+	let (interaction, children) = toggle.into_inner();
+	let Some(mut settings) = settings else {
+		return;
+	};
+	if !interaction.is_changed() || *interaction != Interaction::Pressed {
+		return;
+	}
+	let enabled = !settings.show_ghost;
+	if let Err(e) = settings.update(|settings| settings.show_ghost = enabled) {
+		error!("Failed to save settings: {e}");
+	}
+	let mut texts = texts.iter_many_mut(children);
+	while let Some(mut text) = texts.fetch_next() {
+		text.0 = ghost_label(settings.show_ghost);
+	}
+}
+
+#[derive(Component, Debug, Copy, Clone)]
+pub struct BackButton;
+
+pub fn handle_back_btn(
+	interaction: Single<&Interaction, With<BackButton>>,
+	mut next_state: ResMut<NextState<PauseMenu>>,
+) {
+	if **interaction == Interaction::Pressed {
+		next_state.set(PauseMenu::Main);
+	}
+}
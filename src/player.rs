@@ -1,8 +1,9 @@
-use crate::GameState;
+use crate::interpolation::InterpolatedTranslation;
 use crate::levels::Level;
-use crate::loading::{LoadingTaskHandle, LoadingTasks};
+use crate::loading::{LoadingTasks, report_asset_progress};
 use crate::map::Map;
-use crate::stats::{GameResult, end_level};
+use crate::stats::{GameResult, LevelStats};
+use crate::{GameState, PlayState};
 use bevy::prelude::*;
 use bevy::render::camera;
 use bevy_enhanced_input::prelude::*;
@@ -11,29 +12,42 @@ use serde::{Deserialize, Serialize};
 pub const BASE_PLAYER_MAX_VELOCITY: f32 = 2000.0;
 pub const BASE_PLAYER_ACCEL: f32 = 500.0;
 pub const BASE_PLAYER_VELOCITY_DECAY: f32 = 0.5;
+/// How fast the blades spin, in radians per second.
+pub const BLADES_SPIN_SPEED: f32 = -24.0;
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
 	fn build(&self, app: &mut App) {
-		app.add_input_context::<Avatar>()
-			.add_systems(OnEnter(GameState::Loading), PlayerAssets::load)
+		// Evaluated every fixed step, so replays can reproduce the input to each one.
+		app.add_input_context_to::<FixedPreUpdate, Avatar>()
+			.add_systems(Update, spin_blades)
 			.add_systems(
-				Update,
-				(
-					spin_blades,
-					player_movement.run_if(in_state(GameState::Playing)),
-					PlayerAssets::check_progress.run_if(in_state(GameState::Loading)),
-				),
+				FixedUpdate,
+				(player_movement, sweep_blades, end_blades_sweep)
+					.chain()
+					.run_if(in_state(PlayState::Running)),
 			)
 			.add_systems(OnEnter(GameState::Playing), spawn_player);
 	}
 }
 
-#[derive(Resource, Debug, Clone)]
+/// Loads [`PlayerAssets`] for each level.
+pub struct PlayerAssetsPlugin;
+
+impl Plugin for PlayerAssetsPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(OnEnter(GameState::Loading), PlayerAssets::load)
+			.add_systems(
+				Update,
+				PlayerAssets::check_progress.run_if(in_state(GameState::Loading)),
+			);
+	}
+}
+
+#[derive(Resource, Debug, Default, Clone)]
 pub struct PlayerAssets {
-	loading_task_handle: LoadingTaskHandle,
-	blades: Handle<Image>,
+	pub blades: Handle<Image>,
 }
 
 impl PlayerAssets {
@@ -42,8 +56,8 @@ impl PlayerAssets {
 		mut loading_tasks: ResMut<LoadingTasks>,
 		server: Res<AssetServer>,
 	) {
+		let _ = loading_tasks.start("Player Assets");
 		cmds.insert_resource(Self {
-			loading_task_handle: loading_tasks.start("Player Assets"),
 			blades: server.load("blades.png"),
 		});
 	}
@@ -53,12 +67,15 @@ impl PlayerAssets {
 		server: Res<AssetServer>,
 		mut loading_tasks: ResMut<LoadingTasks>,
 	) {
-		for handle in &[assets.blades.clone().untyped()] {
-			if !server.is_loaded_with_dependencies(handle.id()) {
-				return;
-			}
-		}
-		loading_tasks.finish(assets.loading_task_handle);
+		let Some(task) = loading_tasks.find("Player Assets") else {
+			return;
+		};
+		report_asset_progress(
+			&mut loading_tasks,
+			task,
+			&server,
+			&[assets.blades.clone().untyped()],
+		);
 	}
 }
 
@@ -83,7 +100,7 @@ pub fn spawn_player(mut cmds: Commands, assets: Res<PlayerAssets>) {
 		cmds.spawn((
 			Blades {
 				radius: 48.0,
-				spin_speed: -24.0,
+				spin_speed: BLADES_SPIN_SPEED,
 			},
 			Sprite {
 				image: assets.blades.clone(),
@@ -105,15 +122,22 @@ pub fn spawn_player(mut cmds: Commands, assets: Res<PlayerAssets>) {
 }
 
 #[derive(Component, Debug, Default, Copy, Clone, Reflect)]
-#[require(Transform, Visibility, Velocity, StateScoped::<GameState>(GameState::Playing))]
+#[require(
+	Transform,
+	Visibility,
+	Velocity,
+	InterpolatedTranslation,
+	StateScoped::<GameState>(GameState::Playing)
+)]
 pub struct Avatar;
 
 pub fn player_movement(
-	mut cmds: Commands,
 	mut query: Query<(&mut Transform, &mut Velocity), With<Avatar>>,
+	mut sweeps: Query<&mut BladesSweep>,
 	move_action: Single<&Action<Move>>,
 	level: Res<Level>,
 	map: Res<Map>,
+	mut stats: ResMut<LevelStats>,
 	t: Res<Time>,
 ) {
 	for (mut xform, mut vel) in &mut query {
@@ -129,8 +153,23 @@ pub fn player_movement(
 		vel.0 = vel.0.clamp_length_max(max_velocity);
 		xform.translation += vel.0.extend(0.0) * t.delta_secs();
 		let abs_pos = xform.translation.xy().abs();
-		if abs_pos.x > map.size.x * 0.5 || abs_pos.y > map.size.y * 0.5 {
-			cmds.run_system_cached_with(end_level, GameResult::OutOfBounds);
+		let lives = level.goal.lives();
+		if (abs_pos.x > map.size.x * 0.5 || abs_pos.y > map.size.y * 0.5)
+			&& stats.lives_lost < lives
+		{
+			// `check_goal` ends the level if this was the last life.
+			stats.lives_lost += 1;
+			stats.last_life_lost = Some(GameResult::OutOfBounds);
+			// Leave the player where they left the map, for the level end screen.
+			if stats.lives_lost >= lives {
+				continue;
+			}
+			xform.translation = Vec3::new(0.0, 0.0, xform.translation.z);
+			vel.0 = Vec2::ZERO;
+			// Don't sweep the blades all the way back to the center.
+			for mut sweep in &mut sweeps {
+				sweep.from = None;
+			}
 		}
 	}
 }
@@ -163,11 +202,83 @@ pub fn spin_blades(mut query: Query<(&mut Transform, &Blades)>, t: Res<Time>) {
 }
 
 #[derive(Component, Debug, Default, Copy, Clone)]
+#[require(BladesSweep)]
 pub struct Blades {
 	pub radius: f32,
 	pub spin_speed: f32,
 }
 
+/// Where the blades were the last time collisions were checked, so that moving
+/// further than their radius in one step doesn't skip over anything in between.
+#[derive(Component, Debug, Default, Copy, Clone)]
+pub struct BladesSweep {
+	/// `None` until the first check, and after the player respawns.
+	pub from: Option<Vec2>,
+	/// Where the blades are in the current step.
+	pub to: Vec2,
+}
+
+/// Collision systems for [`Blades`] must run between this and [`end_blades_sweep`].
+pub fn sweep_blades(
+	mut blades: Query<(&Transform, Option<&ChildOf>, &mut BladesSweep)>,
+	parents: Query<&Transform, Without<BladesSweep>>,
+) {
+	for (xform, child_of, mut sweep) in &mut blades {
+		// `GlobalTransform` is only updated once per frame, but there may be several steps per frame.
+		let parent = child_of
+			.and_then(|child_of| parents.get(child_of.parent()).ok())
+			.copied()
+			.unwrap_or_default();
+		sweep.to = parent.transform_point(xform.translation).xy();
+	}
+}
+
+pub fn end_blades_sweep(mut query: Query<&mut BladesSweep>) {
+	for mut sweep in &mut query {
+		sweep.from = Some(sweep.to);
+	}
+}
+
+/// The capsule covering everywhere the blades have been since the last step.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SweptBlades {
+	pub start: Vec2,
+	pub end: Vec2,
+	pub radius: f32,
+}
+
+impl SweptBlades {
+	pub fn new(blades: &Blades, sweep: &BladesSweep) -> Self {
+		Self {
+			start: sweep.from.unwrap_or(sweep.to),
+			end: sweep.to,
+			radius: blades.radius,
+		}
+	}
+
+	pub fn center(&self) -> Vec2 {
+		self.start.midpoint(self.end)
+	}
+
+	/// The radius around [`center`](Self::center) which contains the whole capsule.
+	pub fn bounding_radius(&self) -> f32 {
+		self.start.distance(self.end) * 0.5 + self.radius
+	}
+
+	/// Whether a circle of `radius` at `pos` overlaps the capsule.
+	pub fn touches(&self, pos: Vec2, radius: f32) -> bool {
+		let path = self.end - self.start;
+		let len_sq = path.length_squared();
+		let t = if len_sq > 0.0 {
+			((pos - self.start).dot(path) / len_sq).clamp(0.0, 1.0)
+		} else {
+			0.0
+		};
+		let closest = self.start + path * t;
+		closest.distance_squared(pos) < (self.radius + radius).powi(2)
+	}
+}
+
 #[derive(InputAction)]
 #[action_output(Vec2)]
 pub struct Move;
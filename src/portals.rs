@@ -1,12 +1,23 @@
-use crate::GameState;
-use crate::bees::Bee;
-use crate::map::TimelinePosition;
+use crate::bees::steering::Steering;
+use crate::bees::{Bee, BeeLifetime};
+use crate::gameplay_assets::GameplayAssets;
+use crate::levels::WavePortal;
+use crate::map::{Map, TimelinePosition, move_timeline_items};
+use crate::player::Avatar;
+use crate::portals::items::{
+	FRIENDLY_RADIUS, HAZARD_RADIUS, ItemLifetime, POWER_UP_RADIUS, PowerUpKind,
+};
+use crate::rng::GameRng;
+use crate::{GameState, PlayState};
 use bevy::color::palettes::basic::BLUE;
 use bevy::input::common_conditions::input_toggle_active;
 use bevy::prelude::*;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+pub mod items;
+
 pub struct PortalsPlugin;
 
 impl Plugin for PortalsPlugin {
@@ -14,23 +25,44 @@ impl Plugin for PortalsPlugin {
 		if cfg!(feature = "dev_tools") {
 			app.add_systems(Update, dbg_draw_portals.run_if(input_toggle_active(false, KeyCode::KeyP)));
 		}
-		app.add_systems(
-			Update,
-			(
-				spin_portals,
-				spawn_items.run_if(in_state(GameState::Playing)),
-			),
-		);
+		app.add_plugins(items::ItemsPlugin)
+			.register_type::<Portal>()
+			.add_event::<SpawnItem>()
+			.add_systems(Update, spin_portals)
+			.add_systems(
+				FixedUpdate,
+				(
+					(
+						spawn_items.after(move_timeline_items),
+						despawn_exhausted_sub_portals,
+					)
+						.run_if(in_state(PlayState::Running)),
+					spawn_sub_portals.after(spawn_items),
+				),
+			)
+			.add_systems(
+				PostUpdate,
+				decorate_portals.run_if(resource_exists::<GameplayAssets>),
+			);
 	}
 }
 
+/// Portals spawned by waves follow the map curve via a [`TimelinePosition`],
+/// but ones placed in a level scene may omit it to stay put.
 #[derive(Component, Reflect, Debug, Clone, Serialize, Deserialize)]
-#[reflect(Default, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
 #[serde(default)]
-#[require(TimelinePosition, Transform, StateScoped::<GameState>(GameState::LevelEnd))]
+#[require(Transform, Sprite, StateScoped::<GameState>(GameState::LevelEnd))]
 pub struct Portal {
 	pub spawn_timer: Timer,
 	pub spawns: SpawnedItem,
+	/// How many more items this portal will spawn. `None` means it never runs out.
+	pub remaining_spawns: Option<u32>,
+	pub pattern: SpawnPattern,
+	/// How far from the center of the portal items are spawned.
+	pub spawn_offset: f32,
+	/// The current rotation of a [`SpawnPattern::Spiral`], in radians.
+	pub spiral_angle: f32,
 }
 
 impl Default for Portal {
@@ -38,10 +70,20 @@ impl Default for Portal {
 		Self {
 			spawn_timer: Timer::new(Duration::from_secs_f32(0.05), TimerMode::Repeating),
 			spawns: default(),
+			remaining_spawns: None,
+			pattern: default(),
+			spawn_offset: 32.0,
+			spiral_angle: 0.0,
 		}
 	}
 }
 
+impl Portal {
+	pub fn is_exhausted(&self) -> bool {
+		self.remaining_spawns == Some(0)
+	}
+}
+
 #[derive(Component, Debug, Clone, Copy)]
 #[require(Sprite, Transform::from_translation(Vec3::NEG_Z))]
 pub struct PortalSwirls;
@@ -55,16 +97,24 @@ pub struct PortalDescriptor {
 	pub spawns: SpawnedItem,
 	pub t_start: f32,
 	pub speed: f32,
+	/// Total number of items to spawn before the portal is used up. Unlimited if `None`.
+	pub max_spawns: Option<u32>,
+	pub pattern: SpawnPattern,
+	pub spawn_offset: f32,
 }
 
 impl Default for PortalDescriptor {
 	fn default() -> Self {
 		let tpos = TimelinePosition::default();
+		let portal = Portal::default();
 		Self {
-			spawn_interval: Duration::from_secs_f32(0.05),
-			spawns: Portal::default().spawns,
+			spawn_interval: portal.spawn_timer.duration(),
+			spawns: portal.spawns,
 			t_start: tpos.t,
 			speed: tpos.speed,
+			max_spawns: portal.remaining_spawns,
+			pattern: portal.pattern,
+			spawn_offset: portal.spawn_offset,
 		}
 	}
 }
@@ -74,7 +124,11 @@ impl PortalDescriptor {
 		(
 			Portal {
 				spawn_timer: Timer::new(self.spawn_interval, TimerMode::Repeating),
-				spawns: self.spawns,
+				spawns: self.spawns.clone(),
+				remaining_spawns: self.max_spawns,
+				pattern: self.pattern,
+				spawn_offset: self.spawn_offset,
+				spiral_angle: 0.0,
 			},
 			TimelinePosition {
 				t: self.t_start,
@@ -82,54 +136,275 @@ impl PortalDescriptor {
 			},
 		)
 	}
+
+	/// Whether the portal, or any sub-portal it spawns, keeps spawning forever.
+	pub fn is_unlimited(&self) -> bool {
+		let sub_portals = match &self.spawns {
+			SpawnedItem::SubPortals(sub_portals) => sub_portals.as_slice(),
+			_ => &[],
+		};
+		self.max_spawns.is_none() || sub_portals.iter().any(Self::is_unlimited)
+	}
 }
 
-#[derive(Reflect, Debug, Clone, Copy, Serialize, Deserialize)]
-#[reflect(Default, Serialize, Deserialize)]
+#[derive(Reflect, Debug, Clone, Serialize, Deserialize)]
+#[reflect(no_field_bounds, Default, Serialize, Deserialize)]
 pub enum SpawnedItem {
-	Bees { speed: f32 },
+	/// See [`Bee`] for what each field does.
+	Bees {
+		speed: f32,
+		#[serde(default)]
+		steering: Steering,
+		#[serde(default = "default_bee_radius")]
+		radius: f32,
+		#[serde(default)]
+		health: Option<u32>,
+		#[serde(default = "default_bee_score")]
+		score: u32,
+		#[serde(default)]
+		lifetime: BeeLifetime,
+	},
+	/// See [`items::Hazard`]. It disappears after `lifetime`, and `radius` is how close the blades
+	/// have to come to hit it.
+	Hazard {
+		speed: f32,
+		#[serde(default = "default_hazard_radius")]
+		radius: f32,
+		#[serde(default = "default_item_lifetime")]
+		lifetime: Duration,
+	},
+	/// A stationary pickup which disappears after `lifetime`.
+	PowerUp {
+		kind: PowerUpKind,
+		lifetime: Duration,
+		#[serde(default = "default_power_up_radius")]
+		radius: f32,
+	},
+	/// See [`items::Friendly`]. `radius` and `lifetime` work as they do for hazards.
+	Friendly {
+		speed: f32,
+		penalty: u32,
+		#[serde(default = "default_friendly_radius")]
+		radius: f32,
+		#[serde(default = "default_item_lifetime")]
+		lifetime: Duration,
+	},
+	/// Spawns a stationary [`SubPortal`] for each descriptor. Their `t_start` and `speed` are ignored.
+	SubPortals(Vec<PortalDescriptor>),
 }
 
 impl Default for SpawnedItem {
 	fn default() -> Self {
-		Self::Bees { speed: 400.0 }
+		let bee = Bee::default();
+		Self::Bees {
+			speed: bee.speed,
+			steering: default(),
+			radius: bee.radius,
+			health: bee.health,
+			score: bee.score,
+			lifetime: default(),
+		}
+	}
+}
+
+fn default_bee_radius() -> f32 {
+	Bee::default().radius
+}
+
+fn default_bee_score() -> u32 {
+	Bee::default().score
+}
+
+fn default_hazard_radius() -> f32 {
+	HAZARD_RADIUS
+}
+
+fn default_power_up_radius() -> f32 {
+	POWER_UP_RADIUS
+}
+
+fn default_friendly_radius() -> f32 {
+	FRIENDLY_RADIUS
+}
+
+fn default_item_lifetime() -> Duration {
+	ItemLifetime::default().duration()
+}
+
+/// How the items spawned on each tick of a portal's timer are arranged.
+///
+/// Angles are in radians, measured counterclockwise from straight up.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[reflect(Default, Serialize, Deserialize)]
+pub enum SpawnPattern {
+	/// Each item gets its own uniformly random direction.
+	Random { count: u32 },
+	/// Evenly spaced arms which rotate at `angular_velocity` radians per second.
+	Spiral { count: u32, angular_velocity: f32 },
+	/// A ring of evenly spaced items.
+	Burst { count: u32 },
+	/// Items spread across a `spread`-radian arc centered on the direction of the player.
+	AimedFan { count: u32, spread: f32 },
+	/// Items spread across a `spread`-radian arc centered on the direction the portal is moving
+	/// along the map curve. Portals without a [`TimelinePosition`] spawn in random directions.
+	Tangent { count: u32, spread: f32 },
+}
+
+impl Default for SpawnPattern {
+	fn default() -> Self {
+		Self::Random { count: 1 }
+	}
+}
+
+impl SpawnPattern {
+	/// The angles to spawn one tick's worth of items at, given the direction the pattern is aimed.
+	pub fn angles(&self, aim: f32, rng: &mut impl Rng) -> Vec<f32> {
+		match *self {
+			SpawnPattern::Random { count } => (0..count)
+				.map(|_| rng.random::<f32>() * std::f32::consts::TAU)
+				.collect(),
+			SpawnPattern::Spiral { count, .. } | SpawnPattern::Burst { count } => (0..count)
+				.map(|i| aim + std::f32::consts::TAU * i as f32 / count as f32)
+				.collect(),
+			SpawnPattern::AimedFan { count, spread } | SpawnPattern::Tangent { count, spread } => {
+				if count <= 1 {
+					return vec![aim; count as usize];
+				}
+				(0..count)
+					.map(|i| aim - spread * 0.5 + spread * i as f32 / (count - 1) as f32)
+					.collect()
+			}
+		}
 	}
 }
 
+/// Sent for each item a portal spawns. Every kind of [`SpawnedItem`] has its own system which reads
+/// these and spawns the items of that kind.
+#[derive(Event, Debug, Clone)]
+pub struct SpawnItem {
+	pub item: SpawnedItem,
+	pub transform: Transform,
+	/// Where the portal which spawned the item was.
+	pub origin: Vec2,
+	/// The portal which spawned the item.
+	pub portal: Entity,
+}
+
 pub fn spawn_items(
-	mut cmds: Commands,
-	mut portals: Query<(&mut Portal, &GlobalTransform)>,
-	server: Res<AssetServer>,
+	mut spawn_events: EventWriter<SpawnItem>,
+	// Portals are expected to either be top-level or children of the level scene, which stays at the
+	// origin, since `GlobalTransform` is only updated once per frame rather than every step.
+	mut portals: Query<(Entity, &mut Portal, &Transform, Option<&TimelinePosition>)>,
+	player: Option<Single<&Transform, With<Avatar>>>,
+	map: Option<Res<Map>>,
+	mut rng: ResMut<GameRng>,
 	t: Res<Time>,
 ) {
-	for (mut portal, xform) in &mut portals {
+	for (id, mut portal, xform, tpos) in &mut portals {
 		portal.spawn_timer.tick(t.delta());
-		let xform = xform.compute_transform();
-		for _ in 0..portal.spawn_timer.times_finished_this_tick() {
-			match portal.spawns {
-				SpawnedItem::Bees { speed } => {
-					let rot = rand::random::<f32>() * std::f32::consts::TAU;
-					let dir = Vec2::from_angle(rot).rotate(Vec2::Y);
-					let pos = xform.translation.xy() + dir * 32.0;
-					cmds.spawn((
-						Bee { speed },
-						Sprite {
-							// TODO: Load this in loading state
-							image: server.load("bee.png"),
-							..default()
-						},
-						Transform {
-							translation: pos.extend(0.0),
-							rotation: Quat::from_rotation_z(rot),
-							..default()
-						},
-					));
+		if let SpawnPattern::Spiral {
+			angular_velocity, ..
+		} = portal.pattern
+		{
+			portal.spiral_angle =
+				(portal.spiral_angle + angular_velocity * t.delta_secs()) % std::f32::consts::TAU;
+		}
+		let pos = xform.translation.xy();
+		let aim = match portal.pattern {
+			SpawnPattern::Random { .. } | SpawnPattern::Burst { .. } => 0.0,
+			SpawnPattern::Spiral { .. } => portal.spiral_angle,
+			SpawnPattern::AimedFan { .. } => player
+				.as_deref()
+				.map(|player| Vec2::Y.angle_to(player.translation.xy() - pos))
+				.unwrap_or_else(|| rng.random::<f32>() * std::f32::consts::TAU),
+			SpawnPattern::Tangent { .. } => map
+				.as_deref()
+				.zip(tpos)
+				.map(|(map, tpos)| Vec2::Y.angle_to(map.velocity(tpos.t) * tpos.speed.signum()))
+				.unwrap_or_else(|| rng.random::<f32>() * std::f32::consts::TAU),
+		};
+		'ticks: for _ in 0..portal.spawn_timer.times_finished_this_tick() {
+			for rot in portal.pattern.angles(aim, &mut *rng) {
+				if let Some(remaining) = &mut portal.remaining_spawns {
+					if *remaining == 0 {
+						break 'ticks;
+					}
+					*remaining -= 1;
+				}
+				let dir = Vec2::from_angle(rot).rotate(Vec2::Y);
+				let pos = pos + dir * portal.spawn_offset;
+				spawn_events.write(SpawnItem {
+					item: portal.spawns.clone(),
+					transform: Transform {
+						translation: pos.extend(0.0),
+						rotation: Quat::from_rotation_z(rot),
+						..default()
+					},
+					origin: xform.translation.xy(),
+					portal: id,
+				});
+			}
+		}
+	}
+}
+
+/// Marks portals spawned by other portals. They stay where they were spawned and are despawned once
+/// exhausted, so they should usually be given a `max_spawns`.
+#[derive(Component, Debug, Copy, Clone)]
+pub struct SubPortal;
+
+pub fn spawn_sub_portals(
+	mut cmds: Commands,
+	mut events: EventReader<SpawnItem>,
+	wave_portals: Query<(), With<WavePortal>>,
+) {
+	for event in events.read() {
+		if let SpawnedItem::SubPortals(descriptors) = &event.item {
+			for descriptor in descriptors {
+				let (portal, _) = descriptor.bundle();
+				let mut sub_portal = cmds.spawn((
+					portal,
+					SubPortal,
+					Transform::from_translation(event.transform.translation),
+				));
+				// Part of the wave, so cleaned up with it and waited for by `PortalsExhausted`.
+				if wave_portals.contains(event.portal) {
+					sub_portal.insert(WavePortal);
 				}
 			}
 		}
 	}
 }
 
+pub fn despawn_exhausted_sub_portals(
+	mut cmds: Commands,
+	portals: Query<(Entity, &Portal), With<SubPortal>>,
+) {
+	for (id, portal) in &portals {
+		if portal.is_exhausted() {
+			cmds.entity(id).despawn();
+		}
+	}
+}
+
+/// Gives newly added portals their sprites, whether they were spawned by a wave or a level scene.
+pub fn decorate_portals(
+	mut cmds: Commands,
+	mut portals: Query<(Entity, &mut Sprite), Added<Portal>>,
+	assets: Res<GameplayAssets>,
+) {
+	for (id, mut sprite) in &mut portals {
+		sprite.image = assets.portal.clone();
+		cmds.entity(id).with_child((
+			PortalSwirls,
+			Sprite {
+				image: assets.portal_swirls.clone(),
+				..default()
+			},
+		));
+	}
+}
+
 pub fn spin_portals(
 	mut portals: Query<&mut Transform, (With<Portal>, Without<PortalSwirls>)>,
 	mut swirls: Query<&mut Transform, (With<PortalSwirls>, Without<Portal>)>,
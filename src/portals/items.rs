@@ -0,0 +1,308 @@
+use crate::bees::BASE_BEE_RADIUS;
+use crate::gameplay_assets::GameplayAssets;
+use crate::interpolation::InterpolatedTranslation;
+use crate::player::{Blades, BladesSweep, SweptBlades, end_blades_sweep, sweep_blades};
+use crate::portals::{SpawnItem, SpawnedItem, spawn_items};
+use crate::stats::{GameResult, LevelStats};
+use crate::{GameState, PlayState};
+use bevy::color::palettes::basic::{AQUA, LIME, RED};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Spawnable items other than bees and portals.
+pub struct ItemsPlugin;
+
+impl Plugin for ItemsPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(
+			FixedUpdate,
+			(
+				(spawn_hazards, spawn_power_ups, spawn_friendlies).after(spawn_items),
+				(
+					(
+						move_items,
+						(hit_hazards, collect_power_ups, kill_friendlies)
+							.after(sweep_blades)
+							.before(end_blades_sweep),
+						expire_items,
+					)
+						.chain(),
+					tick_blades_boost.after(end_blades_sweep),
+				)
+					.run_if(in_state(PlayState::Running)),
+			),
+		)
+		.add_systems(
+			PostUpdate,
+			decorate_items.run_if(resource_exists::<GameplayAssets>),
+		);
+	}
+}
+
+/// The default [`ItemRadius`] of a hazard.
+pub const HAZARD_RADIUS: f32 = 24.0;
+/// The default [`ItemRadius`] of a power-up.
+pub const POWER_UP_RADIUS: f32 = 20.0;
+/// The default [`ItemRadius`] of a friendly, which looks like a bee.
+pub const FRIENDLY_RADIUS: f32 = BASE_BEE_RADIUS;
+
+/// Costs the player a life when touched.
+#[derive(Component, Debug, Copy, Clone)]
+#[require(
+	Sprite,
+	ItemSpeed,
+	ItemRadius,
+	ItemLifetime,
+	InterpolatedTranslation,
+	StateScoped::<GameState>(GameState::LevelEnd)
+)]
+pub struct Hazard;
+
+/// Grants a [`PowerUpKind`] when touched.
+#[derive(Component, Debug, Copy, Clone)]
+#[require(
+	Sprite,
+	ItemRadius,
+	ItemLifetime,
+	StateScoped::<GameState>(GameState::LevelEnd)
+)]
+pub struct PowerUp(pub PowerUpKind);
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub enum PowerUpKind {
+	/// Gives back one lost life.
+	ExtraLife,
+	/// Scales up the blades' radius by `factor` for `duration`.
+	BiggerBlades { factor: f32, duration: Duration },
+}
+
+/// Takes `penalty` away from the player's score when killed.
+#[derive(Component, Debug, Copy, Clone)]
+#[require(
+	Sprite,
+	ItemSpeed,
+	ItemRadius,
+	ItemLifetime,
+	InterpolatedTranslation,
+	StateScoped::<GameState>(GameState::LevelEnd)
+)]
+pub struct Friendly {
+	pub penalty: u32,
+}
+
+/// How fast an item moves in the direction it is facing.
+#[derive(Component, Debug, Default, Copy, Clone, Deref, DerefMut)]
+pub struct ItemSpeed(pub f32);
+
+/// Added to the blades' radius when checking whether they touch an item. Sprites are sized to match.
+#[derive(Component, Debug, Default, Copy, Clone, Deref, DerefMut)]
+pub struct ItemRadius(pub f32);
+
+/// Items fade out and despawn once this runs out, without counting as missed.
+#[derive(Component, Debug, Clone, Deref, DerefMut)]
+pub struct ItemLifetime(pub Timer);
+
+impl Default for ItemLifetime {
+	fn default() -> Self {
+		Self(Timer::from_seconds(1.0, TimerMode::Once))
+	}
+}
+
+pub fn spawn_hazards(mut cmds: Commands, mut events: EventReader<SpawnItem>) {
+	for event in events.read() {
+		if let SpawnedItem::Hazard {
+			speed,
+			radius,
+			lifetime,
+		} = event.item
+		{
+			cmds.spawn((
+				Hazard,
+				ItemSpeed(speed),
+				ItemRadius(radius),
+				ItemLifetime(Timer::new(lifetime, TimerMode::Once)),
+				event.transform,
+			));
+		}
+	}
+}
+
+pub fn spawn_power_ups(mut cmds: Commands, mut events: EventReader<SpawnItem>) {
+	for event in events.read() {
+		if let SpawnedItem::PowerUp {
+			kind,
+			lifetime,
+			radius,
+		} = event.item
+		{
+			cmds.spawn((
+				PowerUp(kind),
+				ItemRadius(radius),
+				ItemLifetime(Timer::new(lifetime, TimerMode::Once)),
+				event.transform,
+			));
+		}
+	}
+}
+
+pub fn spawn_friendlies(mut cmds: Commands, mut events: EventReader<SpawnItem>) {
+	for event in events.read() {
+		if let SpawnedItem::Friendly {
+			speed,
+			penalty,
+			radius,
+			lifetime,
+		} = event.item
+		{
+			cmds.spawn((
+				Friendly { penalty },
+				ItemSpeed(speed),
+				ItemRadius(radius),
+				ItemLifetime(Timer::new(lifetime, TimerMode::Once)),
+				event.transform,
+			));
+		}
+	}
+}
+
+type NewItemsFilter = Or<(Added<Hazard>, Added<PowerUp>, Added<Friendly>)>;
+type NewItemsData<'a> = (&'a mut Sprite, &'a ItemRadius, Has<Hazard>, Has<PowerUp>);
+
+/// Gives newly spawned items their sprites.
+pub fn decorate_items(mut items: Query<NewItemsData, NewItemsFilter>, assets: Res<GameplayAssets>) {
+	for (mut sprite, radius, hazard, power_up) in &mut items {
+		if hazard {
+			sprite.image = assets.portal_swirls.clone();
+			sprite.color = RED.into();
+		} else if power_up {
+			sprite.image = assets.portal.clone();
+			sprite.color = LIME.into();
+		} else {
+			sprite.image = assets.bee.clone();
+			sprite.color = AQUA.into();
+		}
+		sprite.custom_size = Some(Vec2::splat(**radius * 2.0));
+	}
+}
+
+pub fn move_items(mut query: Query<(&mut Transform, &ItemSpeed)>, t: Res<Time>) {
+	for (mut xform, speed) in &mut query {
+		let dir = xform.rotation * Vec3::Y;
+		xform.translation += dir * t.delta_secs() * **speed;
+	}
+}
+
+pub fn expire_items(
+	mut cmds: Commands,
+	mut query: Query<(Entity, &mut ItemLifetime, &mut Sprite)>,
+	t: Res<Time>,
+) {
+	for (id, mut lifetime, mut sprite) in &mut query {
+		lifetime.tick(t.delta());
+		let percent = lifetime.fraction();
+		sprite.color.set_alpha(1.0 - (percent * percent));
+		if lifetime.finished() {
+			cmds.entity(id).despawn();
+		}
+	}
+}
+
+fn touching_blades(
+	blades: &Query<(&Blades, &BladesSweep)>,
+	xform: &Transform,
+	radius: &ItemRadius,
+) -> bool {
+	blades.iter().any(|(blades, sweep)| {
+		SweptBlades::new(blades, sweep).touches(xform.translation.xy(), **radius)
+	})
+}
+
+pub fn hit_hazards(
+	mut cmds: Commands,
+	hazards: Query<(Entity, &Transform, &ItemRadius), With<Hazard>>,
+	blades: Query<(&Blades, &BladesSweep)>,
+	mut stats: ResMut<LevelStats>,
+) {
+	for (id, xform, radius) in &hazards {
+		if touching_blades(&blades, xform, radius) {
+			cmds.entity(id).despawn();
+			// `check_goal` ends the level if this was the last life.
+			stats.lives_lost += 1;
+			stats.last_life_lost = Some(GameResult::HitHazard);
+		}
+	}
+}
+
+pub fn kill_friendlies(
+	mut cmds: Commands,
+	friendlies: Query<(Entity, &Transform, &ItemRadius, &Friendly)>,
+	blades: Query<(&Blades, &BladesSweep)>,
+	mut stats: ResMut<LevelStats>,
+) {
+	for (id, xform, radius, friendly) in &friendlies {
+		if touching_blades(&blades, xform, radius) {
+			cmds.entity(id).despawn();
+			stats.score = stats.score.saturating_sub(friendly.penalty);
+		}
+	}
+}
+
+/// Temporarily scales up the [`Blades`] it is on.
+#[derive(Component, Debug, Clone)]
+pub struct BladesBoost {
+	base_radius: f32,
+	timer: Timer,
+}
+
+pub fn collect_power_ups(
+	mut cmds: Commands,
+	power_ups: Query<(Entity, &Transform, &ItemRadius, &PowerUp)>,
+	mut blades: Query<(Entity, &mut Blades, &BladesSweep, Option<&mut BladesBoost>)>,
+	mut stats: ResMut<LevelStats>,
+) {
+	for (id, xform, radius, power_up) in &power_ups {
+		for (blades_id, mut blades, sweep, boost) in &mut blades {
+			if !SweptBlades::new(&blades, sweep).touches(xform.translation.xy(), **radius) {
+				continue;
+			}
+			cmds.entity(id).despawn();
+			match power_up.0 {
+				PowerUpKind::ExtraLife => {
+					stats.lives_lost = stats.lives_lost.saturating_sub(1);
+				}
+				PowerUpKind::BiggerBlades { factor, duration } => {
+					let timer = Timer::new(duration, TimerMode::Once);
+					let base_radius = if let Some(mut boost) = boost {
+						boost.timer = timer;
+						boost.base_radius
+					} else {
+						let base_radius = blades.radius;
+						cmds.entity(blades_id)
+							.insert(BladesBoost { base_radius, timer });
+						base_radius
+					};
+					blades.radius = base_radius * factor;
+				}
+			}
+			break;
+		}
+	}
+}
+
+pub fn tick_blades_boost(
+	mut cmds: Commands,
+	mut blades: Query<(Entity, &mut Blades, &mut Transform, &mut BladesBoost)>,
+	t: Res<Time>,
+) {
+	for (id, mut blades, mut xform, mut boost) in &mut blades {
+		boost.timer.tick(t.delta());
+		if boost.timer.finished() {
+			blades.radius = boost.base_radius;
+			cmds.entity(id).remove::<BladesBoost>();
+		}
+		let scale = blades.radius / boost.base_radius;
+		xform.scale = Vec3::new(scale, scale, 1.0);
+	}
+}
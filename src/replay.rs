@@ -0,0 +1,284 @@
+use crate::levels::{Level, LevelList};
+use crate::player::{Move, player_movement};
+use crate::save::SaveDir;
+use crate::stats::{LevelStats, RunStats};
+use crate::{GameState, PlayState};
+use bevy::asset::ron;
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+pub mod ghost;
+
+/// Records the input of every level attempt so it can be played back later.
+///
+/// Gameplay only depends on the seed of [`GameRng`](crate::rng::GameRng) and the value of the
+/// [`Move`] action in each fixed step, so those are all a replay needs to reproduce an attempt.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_plugins((ghost::GhostPlugin, ReplayRecorderPlugin))
+			// A replay quit partway through must not take over the next level the player starts.
+			.add_systems(OnEnter(GameState::MainMenu), stop_replay)
+			.add_systems(
+				Update,
+				start_pending_replay.run_if(
+					resource_exists::<PendingReplay>
+						.and(resource_exists::<LevelList>)
+						.and(in_state(GameState::MainMenu)),
+				),
+			)
+			.add_systems(
+				FixedPreUpdate,
+				feed_replay_input
+					.before(EnhancedInputSet::Update)
+					.run_if(resource_exists::<ActiveReplay>.and(in_state(PlayState::Running))),
+			)
+			.add_systems(
+				OnEnter(GameState::LevelEnd),
+				(
+					save_replay.run_if(not(resource_exists::<ActiveReplay>)),
+					finish_replay.run_if(resource_exists::<ActiveReplay>),
+				)
+					.chain(),
+			);
+	}
+}
+
+/// Records the value of the [`Move`] action in every fixed step of the level into [`ReplayRecorder`].
+///
+/// Shared by [`ReplayPlugin`] and [`headless_app`](crate::sim::headless_app), so simulations are
+/// recorded the same way as the player.
+pub struct ReplayRecorderPlugin;
+
+impl Plugin for ReplayRecorderPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<ReplayRecorder>()
+			.add_systems(OnEnter(GameState::Playing), start_recording)
+			.add_systems(
+				FixedUpdate,
+				record_input
+					.before(player_movement)
+					.run_if(in_state(PlayState::Running)),
+			);
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+	/// The name of the level in the [`LevelList`].
+	pub level: String,
+	/// The level's map when the replay was recorded, to tell if the level has changed since.
+	pub map: String,
+	pub seed: u64,
+	/// The value of the [`Move`] action in every fixed step while the level was running.
+	pub inputs: Vec<Vec2>,
+	/// The stats the attempt ended with, which playing the replay should reproduce.
+	pub stats: LevelStats,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+	Io(std::io::Error),
+	Ron(ron::Error),
+	Spanned(ron::de::SpannedError),
+}
+
+impl Display for ReplayError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Io(err) => write!(f, "IO error: {err}"),
+			Self::Ron(err) => write!(f, "RON error: {err}"),
+			Self::Spanned(err) => write!(f, "RON error: {err}"),
+		}
+	}
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<std::io::Error> for ReplayError {
+	fn from(value: std::io::Error) -> Self {
+		Self::Io(value)
+	}
+}
+
+impl From<ron::Error> for ReplayError {
+	fn from(value: ron::Error) -> Self {
+		Self::Ron(value)
+	}
+}
+
+impl From<ron::de::SpannedError> for ReplayError {
+	fn from(value: ron::de::SpannedError) -> Self {
+		Self::Spanned(value)
+	}
+}
+
+impl Replay {
+	/// The replay of an attempt at `level` which ended with `stats`.
+	pub fn record(level: &Level, stats: &LevelStats, recorder: &ReplayRecorder) -> Self {
+		Self {
+			level: level.name.clone(),
+			map: level.map.to_string(),
+			seed: stats.seed,
+			inputs: recorder.inputs.clone(),
+			stats: stats.clone(),
+		}
+	}
+
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+		read_ron(path)
+	}
+
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ReplayError> {
+		write_ron(path, self)
+	}
+}
+
+pub fn read_ron<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, ReplayError> {
+	let text = std::fs::read_to_string(path)?;
+	Ok(ron::from_str(&text)?)
+}
+
+/// Writes `value` to `path`, creating any missing parent directories.
+pub fn write_ron<T: Serialize>(path: impl AsRef<Path>, value: &T) -> Result<(), ReplayError> {
+	let path = path.as_ref();
+	if let Some(dir) = path.parent() {
+		std::fs::create_dir_all(dir)?;
+	}
+	std::fs::write(path, ron::to_string(value)?)?;
+	Ok(())
+}
+
+/// The input recorded so far in the current attempt.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct ReplayRecorder {
+	pub inputs: Vec<Vec2>,
+}
+
+/// A replay to start playing once the main menu is reached.
+/// Set with the `--replay` command line argument.
+#[derive(Resource, Debug, Clone)]
+pub struct PendingReplay(pub Replay);
+
+/// The replay being played back, in place of the player's input.
+#[derive(Resource, Debug, Clone)]
+pub struct ActiveReplay {
+	pub replay: Replay,
+	/// The next fixed step to feed input for.
+	pub tick: usize,
+}
+
+pub fn start_recording(mut recorder: ResMut<ReplayRecorder>, replay: Option<ResMut<ActiveReplay>>) {
+	recorder.inputs.clear();
+	// Restarting a level during a replay starts the replay over too.
+	if let Some(mut replay) = replay {
+		replay.tick = 0;
+	}
+}
+
+pub fn record_input(mut recorder: ResMut<ReplayRecorder>, move_action: Single<&Action<Move>>) {
+	recorder.inputs.push(***move_action);
+}
+
+pub fn start_pending_replay(
+	mut cmds: Commands,
+	pending: Res<PendingReplay>,
+	mut level_list: ResMut<LevelList>,
+) {
+	cmds.remove_resource::<PendingReplay>();
+	let replay = pending.0.clone();
+	let Some(i) = level_list
+		.iter()
+		.position(|level| level.name == replay.level)
+	else {
+		error!("Replay is of unknown level {}", replay.level);
+		return;
+	};
+	let map = level_list[i].map.to_string();
+	if map != replay.map {
+		warn!(
+			"Replay was recorded with map {}, but {} now uses {map}",
+			replay.map, replay.level
+		);
+	}
+	info!("Playing replay of {}", replay.level);
+	level_list.1 = i;
+	cmds.insert_resource(level_list[i].clone());
+	cmds.insert_resource(RunStats::new());
+	cmds.insert_resource(ActiveReplay { replay, tick: 0 });
+}
+
+pub fn stop_replay(mut cmds: Commands) {
+	cmds.remove_resource::<ActiveReplay>();
+}
+
+/// Mocks the [`Move`] action for one step with the recorded value, overriding its bindings.
+pub fn feed_replay_input(
+	mut cmds: Commands,
+	mut replay: ResMut<ActiveReplay>,
+	move_action: Single<Entity, With<Action<Move>>>,
+) {
+	let Some(&value) = replay.replay.inputs.get(replay.tick) else {
+		if replay.tick == replay.replay.inputs.len() {
+			warn!("Replay ran out of input before the level ended");
+		}
+		replay.tick += 1;
+		cmds.entity(*move_action).insert(mock_move(Vec2::ZERO));
+		return;
+	};
+	replay.tick += 1;
+	cmds.entity(*move_action).insert(mock_move(value));
+}
+
+/// Makes the [`Move`] action take `value` for the next step, regardless of its bindings.
+pub fn mock_move(value: Vec2) -> ActionMock {
+	let state = if value == Vec2::ZERO {
+		ActionState::None
+	} else {
+		ActionState::Fired
+	};
+	ActionMock::once(state, value)
+}
+
+pub fn save_replay(
+	level: Res<Level>,
+	stats: Res<LevelStats>,
+	recorder: Res<ReplayRecorder>,
+	dir: Res<SaveDir>,
+) {
+	let replay = Replay::record(&level, &stats, &recorder);
+	let path = dir.join("replays").join(format!(
+		"{}-{}.ron",
+		chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"),
+		level.name
+	));
+	#[cfg(not(target_arch = "wasm32"))]
+	match replay.save(&path) {
+		Ok(()) => info!("Saved replay to {}", path.display()),
+		Err(e) => error!("Failed to save replay to {}: {e}", path.display()),
+	}
+	#[cfg(target_arch = "wasm32")]
+	warn!(
+		"Saving replays is not supported on the web yet, discarding {} steps of input for {}",
+		replay.inputs.len(),
+		path.display()
+	);
+}
+
+/// Checks that the replay reproduced the attempt it was recorded from.
+pub fn finish_replay(mut cmds: Commands, replay: Res<ActiveReplay>, stats: Res<LevelStats>) {
+	cmds.remove_resource::<ActiveReplay>();
+	if *stats == replay.replay.stats {
+		info!("Replay of {} finished", replay.replay.level);
+	} else {
+		error!(
+			"Replay of {} diverged from the recording: expected {:?}, got {:?}",
+			replay.replay.level, replay.replay.stats, *stats
+		);
+	}
+}
@@ -0,0 +1,254 @@
+use crate::gameplay_assets::GameplayAssets;
+use crate::interpolation::InterpolatedTranslation;
+use crate::levels::{Level, check_goal};
+use crate::medals::record_personal_best;
+use crate::player::{Avatar, BLADES_SPIN_SPEED, PlayerAssets};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::replay::{read_ron, write_ron};
+use crate::save::{SaveData, SaveDir};
+use crate::settings::Settings;
+use crate::stats::{LevelStats, tick_stats_time};
+use crate::{GameState, PlayState};
+use bevy::color::palettes::basic::{GREEN, RED};
+use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Races the player against a ghost of their personal best on each level.
+pub struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<GhostRecorder>()
+			.add_systems(
+				OnEnter(GameState::Playing),
+				(start_ghost_recording, spawn_ghost),
+			)
+			.add_systems(
+				FixedUpdate,
+				move_ghost
+					.after(tick_stats_time)
+					.run_if(in_state(PlayState::Running).and(resource_exists::<BestGhost>)),
+			)
+			.add_systems(
+				FixedPostUpdate,
+				record_ghost_frame
+					.after(check_goal)
+					.run_if(in_state(PlayState::Running)),
+			)
+			.add_systems(
+				Update,
+				update_ghost_display
+					.run_if(in_state(GameState::Playing).and(resource_exists::<BestGhost>)),
+			)
+			.add_systems(
+				OnEnter(GameState::LevelEnd),
+				save_ghost.after(record_personal_best),
+			);
+	}
+}
+
+/// Where the player was and how many bees they had killed at each fixed step of an attempt.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GhostTrack {
+	pub frames: Vec<GhostFrame>,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct GhostFrame {
+	pub time: Duration,
+	pub position: Vec2,
+	pub killed_bees: u32,
+}
+
+impl GhostTrack {
+	/// The last frame at or before `time`.
+	pub fn at(&self, time: Duration) -> Option<&GhostFrame> {
+		let i = self.frames.partition_point(|frame| frame.time <= time);
+		self.frames.get(i.checked_sub(1)?)
+	}
+
+	/// When the attempt first reached `killed_bees` kills.
+	pub fn time_to(&self, killed_bees: u32) -> Option<Duration> {
+		self.frames
+			.iter()
+			.find(|frame| frame.killed_bees >= killed_bees)
+			.map(|frame| frame.time)
+	}
+
+	pub fn path(dir: &SaveDir, level: &str) -> PathBuf {
+		dir.join("ghosts").join(format!("{level}.ron"))
+	}
+}
+
+/// The track of the current attempt so far.
+#[derive(Resource, Debug, Default, Clone, Deref, DerefMut)]
+pub struct GhostRecorder(pub GhostTrack);
+
+/// The personal best attempt on the current level, if there is one and ghosts are enabled.
+#[derive(Resource, Debug, Clone, Deref, DerefMut)]
+pub struct BestGhost(pub GhostTrack);
+
+#[derive(Component, Debug, Copy, Clone)]
+pub struct GhostAvatar;
+
+/// Compares the current attempt to the [`BestGhost`].
+#[derive(Component, Debug, Copy, Clone)]
+#[require(Text)]
+pub struct GhostDisplay;
+
+pub fn start_ghost_recording(mut recorder: ResMut<GhostRecorder>) {
+	recorder.frames.clear();
+}
+
+pub fn record_ghost_frame(
+	mut recorder: ResMut<GhostRecorder>,
+	player: Single<&Transform, With<Avatar>>,
+	stats: Res<LevelStats>,
+) {
+	recorder.frames.push(GhostFrame {
+		time: stats.time,
+		position: player.translation.xy(),
+		killed_bees: stats.killed_bees,
+	});
+}
+
+/// Keeps the track of this attempt if it just became the personal best.
+pub fn save_ghost(
+	level: Res<Level>,
+	stats: Res<LevelStats>,
+	recorder: Res<GhostRecorder>,
+	save: Option<Res<Persistent<SaveData>>>,
+	dir: Res<SaveDir>,
+) {
+	let is_best = save
+		.as_deref()
+		.and_then(|save| save.personal_bests.get(&level.name))
+		.is_some_and(|best| best.stats == *stats);
+	if !is_best || recorder.frames.is_empty() {
+		return;
+	}
+	let path = GhostTrack::path(&dir, &level.name);
+	#[cfg(not(target_arch = "wasm32"))]
+	if let Err(e) = write_ron(&path, &recorder.0) {
+		error!("Failed to save ghost to {}: {e}", path.display());
+	}
+	#[cfg(target_arch = "wasm32")]
+	warn!(
+		"Saving ghosts is not supported on the web yet, discarding {} frames for {}",
+		recorder.frames.len(),
+		path.display()
+	);
+}
+
+/// The ghost saved at `path` by [`save_ghost`], if there is one.
+#[cfg(not(target_arch = "wasm32"))]
+fn load_ghost(path: &Path) -> Option<GhostTrack> {
+	if !path.exists() {
+		return None;
+	}
+	read_ron(path)
+		.inspect_err(|e| error!("Failed to load ghost from {}: {e}", path.display()))
+		.ok()
+}
+
+/// Ghosts are never saved on the web, so there is never one to load.
+#[cfg(target_arch = "wasm32")]
+fn load_ghost(_path: &Path) -> Option<GhostTrack> {
+	None
+}
+
+pub fn spawn_ghost(
+	mut cmds: Commands,
+	level: Res<Level>,
+	settings: Option<Res<Persistent<Settings>>>,
+	dir: Res<SaveDir>,
+	player_assets: Res<PlayerAssets>,
+	assets: Res<GameplayAssets>,
+) {
+	cmds.remove_resource::<BestGhost>();
+	if settings.is_some_and(|settings| !settings.show_ghost) {
+		return;
+	}
+	let Some(track) = load_ghost(&GhostTrack::path(&dir, &level.name)) else {
+		return;
+	};
+	cmds.insert_resource(BestGhost(track));
+	cmds.spawn((
+		GhostAvatar,
+		Sprite {
+			image: player_assets.blades.clone(),
+			color: Color::WHITE.with_alpha(0.35),
+			..default()
+		},
+		// Just below the player's blades.
+		Transform::from_xyz(0.0, 0.0, 99.0),
+		InterpolatedTranslation::default(),
+		StateScoped::<GameState>(GameState::Playing),
+	));
+	cmds.spawn((
+		Node {
+			position_type: PositionType::Absolute,
+			bottom: Val::Px(10.0),
+			right: Val::Px(10.0),
+			padding: UiRect::all(Val::Px(10.0)),
+			..default()
+		},
+		BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.7)),
+		StateScoped::<GameState>(GameState::LevelEnd),
+	))
+	.with_child((
+		GhostDisplay,
+		TextFont {
+			font: assets.font.clone(),
+			font_size: 24.0,
+			..default()
+		},
+	));
+}
+
+pub fn move_ghost(
+	mut ghost: Single<(&mut Transform, &mut Visibility), With<GhostAvatar>>,
+	best: Res<BestGhost>,
+	stats: Res<LevelStats>,
+	t: Res<Time>,
+) {
+	let (xform, visibility) = &mut *ghost;
+	let Some(frame) = best.at(stats.time) else {
+		return;
+	};
+	**visibility = if stats.time > frame.time + t.delta() {
+		// The best attempt ended before this one.
+		Visibility::Hidden
+	} else {
+		Visibility::Inherited
+	};
+	xform.translation = frame.position.extend(xform.translation.z);
+	xform.rotate_z(t.delta_secs() * BLADES_SPIN_SPEED);
+}
+
+pub fn update_ghost_display(
+	mut display: Single<(&mut Text, &mut TextColor), With<GhostDisplay>>,
+	best: Res<BestGhost>,
+	stats: Res<LevelStats>,
+) {
+	let (text, color) = &mut *display;
+	let best_kills = best.at(stats.time).map_or(0, |frame| frame.killed_bees);
+	let kills_ahead = stats.killed_bees as i64 - best_kills as i64;
+	let pace = match best.time_to(stats.killed_bees) {
+		_ if stats.killed_bees == 0 => "".to_owned(),
+		Some(best_time) => format!(
+			"\n{:+.2}s",
+			stats.time.as_secs_f32() - best_time.as_secs_f32()
+		),
+		None => "\nRecord pace".to_owned(),
+	};
+	text.0 = format!("vs. Best: {kills_ahead:+} bees{pace}");
+	color.0 = match kills_ahead {
+		0 => Color::WHITE,
+		n if n > 0 => GREEN.into(),
+		_ => RED.into(),
+	};
+}
@@ -0,0 +1,47 @@
+use crate::GameState;
+use crate::levels::Level;
+use crate::replay::ActiveReplay;
+use crate::stats::LevelStats;
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<SeedOverride>()
+			.add_systems(OnEnter(GameState::Playing), seed_game_rng);
+	}
+}
+
+/// The source of all randomness in gameplay, reseeded for every attempt at a level so that
+/// an attempt can be reproduced from [`LevelStats::seed`].
+///
+/// ChaCha is used rather than `StdRng` because its output is guaranteed not to change
+/// between versions of `rand`.
+#[derive(Resource, Debug, Clone, Deref, DerefMut)]
+pub struct GameRng(pub ChaCha8Rng);
+
+/// Forces every attempt to use this seed, taking priority over [`Level::seed`]
+/// but not the seed of an [`ActiveReplay`].
+/// Set with the `--seed` command line argument.
+#[derive(Resource, Debug, Default, Copy, Clone)]
+pub struct SeedOverride(pub Option<u64>);
+
+pub fn seed_game_rng(
+	mut cmds: Commands,
+	seed_override: Res<SeedOverride>,
+	replay: Option<Res<ActiveReplay>>,
+	level: Res<Level>,
+	mut stats: ResMut<LevelStats>,
+) {
+	let seed = replay
+		.map(|replay| replay.replay.seed)
+		.or(seed_override.0)
+		.or(level.seed)
+		.unwrap_or_else(rand::random);
+	info!("Seeding level {} with {seed}", level.name);
+	stats.seed = seed;
+	cmds.insert_resource(GameRng(ChaCha8Rng::seed_from_u64(seed)));
+}
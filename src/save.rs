@@ -1,9 +1,10 @@
-use crate::stats::RunStats;
+use crate::medals::Medal;
+use crate::stats::{LevelStats, RunStats};
 use bevy::prelude::*;
 use bevy_persistent::{PersistenceError, Persistent, StorageFormat};
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 
 pub struct SavePlugin;
@@ -21,6 +22,15 @@ impl Plugin for SavePlugin {
 pub struct SaveData {
 	pub runs: BTreeMap<DateTime<Local>, RunStats>,
 	pub unlocked_levels: HashSet<String>,
+	/// Keyed by level name.
+	pub personal_bests: HashMap<String, PersonalBest>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PersonalBest {
+	/// The best medal ever earned on the level, which may not be from the same attempt as `stats`.
+	pub medal: Option<Medal>,
+	pub stats: LevelStats,
 }
 
 pub fn init_save_data(mut cmds: Commands, dir: Res<SaveDir>) {
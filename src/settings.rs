@@ -0,0 +1,45 @@
+use crate::save::ConfigDir;
+use bevy::prelude::*;
+use bevy_persistent::{Persistent, StorageFormat};
+use serde::{Deserialize, Serialize};
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(Startup, init_settings);
+	}
+}
+
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+	/// Pause the game when the window loses focus.
+	pub pause_on_focus_loss: bool,
+	/// Show a ghost of the personal best attempt while playing a level.
+	pub show_ghost: bool,
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Self {
+			pause_on_focus_loss: true,
+			show_ghost: true,
+		}
+	}
+}
+
+pub fn init_settings(mut cmds: Commands, dir: Res<ConfigDir>) {
+	// Unlike save data, there's nothing worth keeping in a settings file that fails to parse.
+	match Persistent::<Settings>::builder()
+		.name("settings")
+		.path(dir.join("settings.ron"))
+		.format(StorageFormat::Ron)
+		.default(Settings::default())
+		.revert_to_default_on_deserialization_errors(true)
+		.build()
+	{
+		Ok(settings) => cmds.insert_resource(settings),
+		Err(e) => error!("Failed to init settings: {e}"),
+	}
+}
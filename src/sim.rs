@@ -0,0 +1,363 @@
+use crate::bees::{Bee, BeesPlugin};
+use crate::gameplay_assets::GameplayAssets;
+use crate::interpolation::InterpolationPlugin;
+use crate::levels::{LEVEL_LOAD_TIMEOUT, Level, LevelList, LevelListHandle, LevelsPlugin};
+use crate::loading::{LoadingFailure, LoadingPlugin, asset_load_failure};
+use crate::map::{Map, MapPlugin};
+use crate::player::{Avatar, Move, PlayerAssets, PlayerPlugin, Velocity};
+use crate::portals::{Portal, PortalsPlugin};
+use crate::replay::{Replay, ReplayRecorder, ReplayRecorderPlugin, mock_move};
+use crate::rng::{RngPlugin, SeedOverride};
+use crate::stats::{GameResult, LevelStats, RunStats, StatsPlugin};
+use crate::{GameState, PlayState};
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::scene::ScenePlugin;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy_enhanced_input::prelude::*;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+/// Takes the [`Move`] action from the [`SimInput`] resource, if there is one, instead of the player.
+///
+/// Part of every [`headless_app`], where each [`App::update`] advances exactly one fixed step,
+/// so a simulation plays out the same way every time it is run.
+pub struct SimPlugin;
+
+impl Plugin for SimPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(
+			FixedPreUpdate,
+			feed_sim_input
+				.before(EnhancedInputSet::Update)
+				.run_if(resource_exists::<SimInput>.and(in_state(PlayState::Running))),
+		);
+	}
+}
+
+/// Builds an app with only the plugins needed for gameplay, loading assets from `asset_dir`.
+///
+/// Nothing is drawn, so images, fonts and loading screens are left out. Like any app updated by
+/// hand, it needs [`App::finish`] and [`App::cleanup`] called once it has all of its plugins.
+pub fn headless_app(asset_dir: impl Into<String>) -> App {
+	let mut app = App::new();
+	app.add_plugins((
+		MinimalPlugins,
+		AssetPlugin {
+			file_path: asset_dir.into(),
+			..default()
+		},
+		StatesPlugin,
+		ScenePlugin,
+		InputPlugin,
+		EnhancedInputPlugin,
+		InterpolationPlugin,
+		ReplayRecorderPlugin,
+		RngPlugin,
+		SimPlugin,
+	))
+	.add_plugins((
+		BeesPlugin,
+		LevelsPlugin,
+		LoadingPlugin,
+		MapPlugin {
+			load_backgrounds: false,
+		},
+		PlayerPlugin,
+		PortalsPlugin,
+		StatsPlugin,
+	))
+	// Placeholders for what `GameplayAssetsPlugin` and `PlayerAssetsPlugin` would load.
+	.init_resource::<GameplayAssets>()
+	.init_resource::<PlayerAssets>()
+	.init_state::<GameState>()
+	.enable_state_scoped_entities::<GameState>()
+	.add_sub_state::<PlayState>()
+	.enable_state_scoped_entities::<PlayState>();
+	let timestep = app.world().resource::<Time<Fixed>>().timestep();
+	app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+	app
+}
+
+/// What the [`InputSource`] of a simulation can see in each fixed step.
+#[derive(Debug)]
+pub struct SimView<'a> {
+	pub level: &'a Level,
+	pub map: &'a Map,
+	pub stats: &'a LevelStats,
+	pub player: Vec2,
+	pub velocity: Vec2,
+	pub bees: &'a [BeeView],
+	pub portals: &'a [Vec2],
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct BeeView {
+	pub position: Vec2,
+	pub velocity: Vec2,
+	pub radius: f32,
+}
+
+/// Decides the value of the [`Move`] action in each fixed step of a simulation.
+///
+/// Any `FnMut(&SimView) -> Vec2` closure is an input source.
+pub trait InputSource: Send + Sync + 'static {
+	fn next_input(&mut self, view: &SimView) -> Vec2;
+}
+
+impl<F: FnMut(&SimView) -> Vec2 + Send + Sync + 'static> InputSource for F {
+	fn next_input(&mut self, view: &SimView) -> Vec2 {
+		self(view)
+	}
+}
+
+/// Plays a fixed sequence of inputs, one per step, then holds [`after`](Self::after).
+#[derive(Debug, Default, Clone)]
+pub struct ScriptedInput {
+	pub steps: Vec<Vec2>,
+	/// The input once every step has been played.
+	pub after: Vec2,
+	/// The next step to play.
+	pub tick: usize,
+}
+
+impl ScriptedInput {
+	/// Stays still once `steps` run out, like a [`Replay`] does.
+	pub fn new(steps: Vec<Vec2>) -> Self {
+		Self {
+			steps,
+			after: Vec2::ZERO,
+			tick: 0,
+		}
+	}
+
+	/// Holds each value from its time until the time of the next, for steps of `timestep`.
+	/// The last value is held for the rest of the simulation.
+	/// `keyframes` must be in order of time.
+	pub fn from_keyframes(
+		keyframes: impl IntoIterator<Item = (Duration, Vec2)>,
+		timestep: Duration,
+	) -> Self {
+		let mut steps = Vec::new();
+		let mut value = Vec2::ZERO;
+		for (time, next) in keyframes {
+			while timestep * (steps.len() as u32) < time {
+				steps.push(value);
+			}
+			value = next;
+		}
+		Self {
+			after: value,
+			..Self::new(steps)
+		}
+	}
+}
+
+impl From<&Replay> for ScriptedInput {
+	fn from(replay: &Replay) -> Self {
+		Self::new(replay.inputs.clone())
+	}
+}
+
+impl InputSource for ScriptedInput {
+	fn next_input(&mut self, _view: &SimView) -> Vec2 {
+		let value = self.steps.get(self.tick).copied().unwrap_or(self.after);
+		self.tick += 1;
+		value
+	}
+}
+
+#[derive(Resource)]
+pub struct SimInput(pub Box<dyn InputSource>);
+
+pub fn feed_sim_input(
+	mut cmds: Commands,
+	mut input: ResMut<SimInput>,
+	move_action: Single<Entity, With<Action<Move>>>,
+	player: Single<(&Transform, &Velocity), With<Avatar>>,
+	bees: Query<(&Transform, &Bee)>,
+	portals: Query<&Transform, With<Portal>>,
+	level: Res<Level>,
+	map: Res<Map>,
+	stats: Res<LevelStats>,
+	mut bee_views: Local<Vec<BeeView>>,
+	mut portal_positions: Local<Vec<Vec2>>,
+) {
+	bee_views.clear();
+	bee_views.extend(bees.iter().map(|(xform, bee)| BeeView {
+		position: xform.translation.xy(),
+		velocity: (xform.rotation * Vec3::Y).xy() * bee.speed,
+		radius: bee.radius,
+	}));
+	portal_positions.clear();
+	portal_positions.extend(portals.iter().map(|xform| xform.translation.xy()));
+	let (xform, vel) = *player;
+	let value = input.0.next_input(&SimView {
+		level: &level,
+		map: &map,
+		stats: &stats,
+		player: xform.translation.xy(),
+		velocity: vel.0,
+		bees: &bee_views,
+		portals: &portal_positions,
+	});
+	cmds.entity(*move_action).insert(mock_move(value));
+}
+
+#[derive(Debug, Clone)]
+pub struct SimConfig {
+	/// The name of the level in the level list.
+	pub level: String,
+	/// Overrides the level's own seed. Levels without one get a random seed otherwise.
+	pub seed: Option<u64>,
+	/// How much level time to simulate before giving up, if the level hasn't ended by then.
+	pub duration: Duration,
+	/// Where to load assets from, in the same form as [`AssetPlugin::file_path`].
+	pub asset_dir: String,
+}
+
+impl Default for SimConfig {
+	fn default() -> Self {
+		Self {
+			level: "Level 1".to_string(),
+			seed: None,
+			duration: Duration::from_secs(600),
+			asset_dir: AssetPlugin::default().file_path,
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct SimOutcome {
+	pub stats: LevelStats,
+	/// `None` if the level was still going after [`SimConfig::duration`].
+	pub result: Option<GameResult>,
+	/// Plays the simulated attempt back in the game.
+	pub replay: Replay,
+}
+
+impl SimOutcome {
+	pub fn won(&self) -> bool {
+		self.result == Some(GameResult::Win)
+	}
+}
+
+#[derive(Debug)]
+pub enum SimError {
+	LevelListFailed(String),
+	UnknownLevel(String),
+	LoadFailed(LoadingFailure),
+	/// Assets took longer than [`LEVEL_LOAD_TIMEOUT`] to load.
+	TimedOut,
+}
+
+impl Display for SimError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::LevelListFailed(reason) => write!(f, "Failed to load level list: {reason}"),
+			Self::UnknownLevel(name) => write!(f, "No level named {name:?}"),
+			Self::LoadFailed(failure) => {
+				write!(f, "Failed to load {}: {}", failure.task, failure.reason)
+			}
+			Self::TimedOut => write!(f, "Timed out loading assets"),
+		}
+	}
+}
+
+impl std::error::Error for SimError {}
+
+/// Plays a level with input from `input` until it ends or [`SimConfig::duration`] runs out.
+///
+/// Meant for tools and tests which need to know how a level plays out, e.g. whether it can be won.
+pub fn simulate(config: &SimConfig, input: impl InputSource) -> Result<SimOutcome, SimError> {
+	let mut app = headless_app(config.asset_dir.clone());
+	app.insert_resource(SeedOverride(config.seed))
+		.insert_resource(SimInput(Box::new(input)));
+	// Normally done by `App::run`.
+	app.finish();
+	app.cleanup();
+
+	let level_list = wait_for_level_list(&mut app)?;
+	let Some(i) = level_list
+		.iter()
+		.position(|level| level.name == config.level)
+	else {
+		return Err(SimError::UnknownLevel(config.level.clone()));
+	};
+	app.insert_resource(LevelList(level_list.0.clone(), i))
+		.insert_resource(level_list[i].clone())
+		.insert_resource(RunStats::new());
+
+	wait_for(&mut app, |world| {
+		match world
+			.get_resource::<State<GameState>>()
+			.map(|state| **state)
+		{
+			Some(GameState::Playing) => Some(Ok(())),
+			Some(GameState::LoadFailed) => Some(Err(SimError::LoadFailed(
+				world.resource::<LoadingFailure>().clone(),
+			))),
+			_ => None,
+		}
+	})??;
+
+	loop {
+		app.update();
+		let world = app.world();
+		let stats = world.resource::<LevelStats>();
+		if **world.resource::<State<GameState>>() != GameState::Playing
+			|| stats.time >= config.duration
+		{
+			break;
+		}
+	}
+
+	let world = app.world();
+	let stats = world.resource::<LevelStats>().clone();
+	Ok(SimOutcome {
+		result: stats.result,
+		replay: Replay::record(&level_list[i], &stats, world.resource::<ReplayRecorder>()),
+		stats,
+	})
+}
+
+/// Loads the level list from `asset_dir`, the same way [`simulate`] does.
+pub fn load_level_list(asset_dir: impl Into<String>) -> Result<LevelList, SimError> {
+	let mut app = headless_app(asset_dir);
+	app.finish();
+	app.cleanup();
+	wait_for_level_list(&mut app)
+}
+
+/// Updates `app` until it has loaded the level list.
+pub fn wait_for_level_list(app: &mut App) -> Result<LevelList, SimError> {
+	wait_for(app, |world| {
+		if let Some(list) = world.get_resource::<LevelList>() {
+			return Some(Ok(list.clone()));
+		}
+		let handle = world.resource::<LevelListHandle>();
+		asset_load_failure(world.resource::<AssetServer>(), &**handle)
+			.map(|reason| Err(SimError::LevelListFailed(reason)))
+	})?
+}
+
+/// Updates `app` until `done` returns something, giving asset loading real time to happen.
+pub fn wait_for<T>(
+	app: &mut App,
+	mut done: impl FnMut(&World) -> Option<T>,
+) -> Result<T, SimError> {
+	let start = Instant::now();
+	// Sleeping for each step keeps loading timeouts, which use virtual time, close to real time.
+	let timestep = app.world().resource::<Time<Fixed>>().timestep();
+	loop {
+		app.update();
+		if let Some(value) = done(app.world()) {
+			return Ok(value);
+		}
+		if start.elapsed() > LEVEL_LOAD_TIMEOUT {
+			return Err(SimError::TimedOut);
+		}
+		std::thread::sleep(timestep);
+	}
+}
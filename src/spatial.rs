@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+
+/// A uniform grid of points, for finding everything near a position without checking every point.
+///
+/// The grid only covers the bounds of the points it was last built from, and is meant to be rebuilt
+/// from scratch whenever the points move, which is a few linear passes over them.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid<T> {
+	cell_size: f32,
+	/// The cell coordinates of the bottom-left cell.
+	min_cell: IVec2,
+	/// How many cells wide and tall the grid is.
+	dims: IVec2,
+	points: Vec<(Vec2, T)>,
+	/// Indices into `points`, grouped by cell in row-major order.
+	order: Vec<u32>,
+	/// Where each cell's indices start in `order`, plus the end of the last cell.
+	cell_starts: Vec<u32>,
+	/// Reused between rebuilds to hold each point's cell.
+	point_cells: Vec<u32>,
+}
+
+impl<T> SpatialGrid<T> {
+	/// Queries are fastest when `cell_size` is around the radius usually queried.
+	pub fn new(cell_size: f32) -> Self {
+		Self {
+			cell_size,
+			min_cell: IVec2::ZERO,
+			dims: IVec2::ZERO,
+			points: Vec::new(),
+			order: Vec::new(),
+			cell_starts: Vec::new(),
+			point_cells: Vec::new(),
+		}
+	}
+
+	pub fn cell_size(&self) -> f32 {
+		self.cell_size
+	}
+
+	pub fn len(&self) -> usize {
+		self.points.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.points.is_empty()
+	}
+
+	/// Every point in the grid, in the order they were given.
+	pub fn points(&self) -> &[(Vec2, T)] {
+		&self.points
+	}
+
+	fn cell(&self, pos: Vec2) -> IVec2 {
+		(pos / self.cell_size).floor().as_ivec2()
+	}
+
+	fn cell_index(&self, cell: IVec2) -> usize {
+		let local = cell - self.min_cell;
+		(local.y * self.dims.x + local.x) as usize
+	}
+
+	/// Replaces every point in the grid, keeping allocations from the last build.
+	pub fn rebuild(&mut self, points: impl IntoIterator<Item = (Vec2, T)>) {
+		self.points.clear();
+		self.points.extend(points);
+		self.order.clear();
+		self.cell_starts.clear();
+		self.point_cells.clear();
+		if self.points.is_empty() {
+			self.dims = IVec2::ZERO;
+			return;
+		}
+
+		let (min, max) = self.points.iter().fold(
+			(Vec2::INFINITY, Vec2::NEG_INFINITY),
+			|(min, max), (pos, _)| (min.min(*pos), max.max(*pos)),
+		);
+		self.min_cell = self.cell(min);
+		self.dims = self.cell(max) - self.min_cell + IVec2::ONE;
+
+		// Counting sort of point indices by cell.
+		self.cell_starts
+			.resize((self.dims.x * self.dims.y) as usize + 1, 0);
+		for i in 0..self.points.len() {
+			let cell = self.cell_index(self.cell(self.points[i].0));
+			self.point_cells.push(cell as u32);
+			self.cell_starts[cell + 1] += 1;
+		}
+		for i in 1..self.cell_starts.len() {
+			self.cell_starts[i] += self.cell_starts[i - 1];
+		}
+		self.order.resize(self.points.len(), 0);
+		// Fill each cell from its end so `cell_starts` ends up back at the start of each cell.
+		for (i, &cell) in self.point_cells.iter().enumerate().rev() {
+			let end = &mut self.cell_starts[cell as usize + 1];
+			*end -= 1;
+			self.order[*end as usize] = i as u32;
+		}
+		// Every cell's start was decremented into the previous entry, so shift them back.
+		self.cell_starts.rotate_left(1);
+		*self.cell_starts.last_mut().unwrap() = self.points.len() as u32;
+	}
+
+	/// Every point within `radius` of `center`.
+	pub fn query_radius(&self, center: Vec2, radius: f32) -> impl Iterator<Item = (Vec2, &T)> {
+		let max_cell = self.min_cell + self.dims - IVec2::ONE;
+		let min = self.cell(center - Vec2::splat(radius)).max(self.min_cell);
+		let max = self.cell(center + Vec2::splat(radius)).min(max_cell);
+		let radius_sq = radius * radius;
+		(min.y..=max.y)
+			// Queries entirely to one side of the grid would otherwise have rows ending before they start.
+			.filter(move |_| min.x <= max.x)
+			.flat_map(move |y| {
+				let row_start = self.cell_index(IVec2::new(min.x, y));
+				let row_end = self.cell_index(IVec2::new(max.x, y)) + 1;
+				let indices =
+					self.cell_starts[row_start] as usize..self.cell_starts[row_end] as usize;
+				self.order[indices].iter()
+			})
+			.map(|&i| &self.points[i as usize])
+			.filter(move |(pos, _)| pos.distance_squared(center) <= radius_sq)
+			.map(|(pos, item)| (*pos, item))
+	}
+}
@@ -0,0 +1,191 @@
+use crate::GameState;
+use crate::levels::{LevelList, LevelListHandle};
+use crate::loading::{LoadingTasks, asset_load_failure, clear_loading_tasks};
+use crate::main_menu::UiAssets;
+use crate::save::SaveData;
+use bevy::input::gamepad::GamepadButtonChangedEvent;
+use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use std::time::Duration;
+
+/// The splash screen stays up at least this long unless skipped.
+pub const SPLASH_MIN_DURATION: Duration = Duration::from_secs(3);
+/// How long to wait for startup assets before showing an error.
+pub const STARTUP_LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+const TITLE_FADE_SECS: f32 = 1.0;
+
+pub struct SplashPlugin;
+
+impl Plugin for SplashPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(
+			OnEnter(GameState::Splash),
+			(UiAssets::load, show_splash).chain(),
+		)
+		.add_systems(
+			Update,
+			(
+				check_startup_loading,
+				UiAssets::check_progress,
+				animate_splash,
+				skip_splash,
+			)
+				.run_if(in_state(GameState::Splash)),
+		)
+		.add_systems(
+			PostUpdate,
+			goto_main_menu.run_if(in_state(GameState::Splash)),
+		)
+		.add_systems(
+			OnExit(GameState::Splash),
+			(clear_loading_tasks, remove_splash_timer),
+		);
+	}
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct SplashTimer {
+	pub timer: Timer,
+	/// Set when the player presses something to skip the rest of the minimum display time.
+	pub skipped: bool,
+}
+
+#[derive(Component, Debug, Copy, Clone)]
+#[require(Text)]
+pub struct SplashTitle;
+type SplashTitleQueryFilter = (With<SplashTitle>, Without<SplashStatus>);
+
+/// Shows loading status until startup is finished, then prompts to skip.
+#[derive(Component, Debug, Copy, Clone)]
+#[require(Text)]
+pub struct SplashStatus;
+type SplashStatusQueryFilter = (With<SplashStatus>, Without<SplashTitle>);
+
+pub fn show_splash(
+	mut cmds: Commands,
+	mut loading_tasks: ResMut<LoadingTasks>,
+	assets: Res<UiAssets>,
+) {
+	info!("Showing splash screen");
+	let _ = loading_tasks.start_with_timeout("Level List", STARTUP_LOAD_TIMEOUT);
+	let _ = loading_tasks.start_with_timeout("Save Data", STARTUP_LOAD_TIMEOUT);
+	cmds.insert_resource(SplashTimer {
+		timer: Timer::new(SPLASH_MIN_DURATION, TimerMode::Once),
+		skipped: false,
+	});
+
+	cmds.spawn((Camera2d, StateScoped::<GameState>(GameState::Splash)));
+	let font = assets.font.clone();
+	cmds.spawn((
+		Node {
+			flex_direction: FlexDirection::Column,
+			align_self: AlignSelf::Center,
+			justify_self: JustifySelf::Center,
+			align_items: AlignItems::Center,
+			row_gap: Val::Px(20.0),
+			..default()
+		},
+		StateScoped::<GameState>(GameState::Splash),
+	))
+	.with_children(|cmds| {
+		cmds.spawn((
+			SplashTitle,
+			Text("Jeremy Bearimy".into()),
+			TextFont {
+				font: font.clone(),
+				font_size: 96.0,
+				..default()
+			},
+			TextColor(Color::WHITE.with_alpha(0.0)),
+		));
+		cmds.spawn((
+			SplashStatus,
+			Text("Loading...".into()),
+			TextFont {
+				font,
+				font_size: 24.0,
+				..default()
+			},
+			TextColor(Color::srgb(0.6, 0.6, 0.6)),
+		));
+	});
+}
+
+pub fn check_startup_loading(
+	mut loading_tasks: ResMut<LoadingTasks>,
+	level_list: Option<Res<LevelList>>,
+	save: Option<Res<Persistent<SaveData>>>,
+	level_list_handle: Res<LevelListHandle>,
+	server: Res<AssetServer>,
+	t: Res<Time>,
+) {
+	loading_tasks.tick_timeouts(t.delta());
+	let level_list_task = loading_tasks.find("Level List").unwrap();
+	if level_list.is_some() {
+		loading_tasks.finish(level_list_task);
+	} else if let Some(reason) = asset_load_failure(&server, &**level_list_handle) {
+		loading_tasks.fail(level_list_task, reason);
+	}
+	let save_task = loading_tasks.find("Save Data").unwrap();
+	if save.is_some() {
+		loading_tasks.finish(save_task);
+	}
+}
+
+pub fn animate_splash(
+	mut timer: ResMut<SplashTimer>,
+	loading_tasks: Res<LoadingTasks>,
+	mut title: Single<&mut TextColor, SplashTitleQueryFilter>,
+	status: Single<(&mut Text, &mut TextColor), SplashStatusQueryFilter>,
+	t: Res<Time>,
+) {
+	timer.timer.tick(t.delta());
+	let elapsed = timer.timer.elapsed_secs();
+	title.0.set_alpha((elapsed / TITLE_FADE_SECS).min(1.0));
+
+	let (mut status_text, mut status_color) = status.into_inner();
+	if let Some((task, reason)) = loading_tasks.failure() {
+		status_text.0 = format!("Failed to load {}: {reason}", task.name());
+		status_color.0 = Color::srgb(0.8, 0.2, 0.2);
+	} else if loading_tasks.iter().all(|(_, status)| status.done()) {
+		status_text.0 = "Press any key to skip".into();
+		// Pulse to draw attention
+		status_color
+			.0
+			.set_alpha(0.6 + 0.4 * (elapsed * std::f32::consts::TAU * 0.5).cos());
+	}
+}
+
+pub fn skip_splash(
+	mut timer: ResMut<SplashTimer>,
+	keys: Res<ButtonInput<KeyCode>>,
+	mouse: Res<ButtonInput<MouseButton>>,
+	mut gamepad_buttons: EventReader<GamepadButtonChangedEvent>,
+) {
+	let gamepad_pressed = gamepad_buttons.read().any(|event| event.state.is_pressed());
+	if keys.get_just_pressed().next().is_some()
+		|| mouse.get_just_pressed().next().is_some()
+		|| gamepad_pressed
+	{
+		timer.skipped = true;
+	}
+}
+
+pub fn goto_main_menu(
+	timer: Res<SplashTimer>,
+	loading_tasks: Res<LoadingTasks>,
+	mut next_state: ResMut<NextState<GameState>>,
+) {
+	if !(timer.skipped || timer.timer.finished()) {
+		return;
+	}
+	if !loading_tasks.iter().all(|(_, status)| status.done()) {
+		return;
+	}
+	info!("Going to main menu");
+	next_state.set(GameState::MainMenu);
+}
+
+pub fn remove_splash_timer(mut cmds: Commands) {
+	cmds.remove_resource::<SplashTimer>();
+}
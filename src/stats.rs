@@ -1,6 +1,9 @@
-use crate::GameState;
-use crate::levels::Level;
+use crate::levels::{Level, LevelList};
+use crate::save::SaveData;
+use crate::{GameState, PlayState};
 use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use chrono::{DateTime, Local};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -9,43 +12,111 @@ pub struct StatsPlugin;
 
 impl Plugin for StatsPlugin {
 	fn build(&self, app: &mut App) {
-		app.add_systems(Update, tick_stats_time.run_if(in_state(GameState::Playing)));
+		app.add_systems(
+			FixedUpdate,
+			tick_stats_time.run_if(in_state(PlayState::Running)),
+		);
 	}
 }
 
-#[derive(Resource, Default, Clone, Debug, Serialize, Deserialize)]
+#[derive(Resource, Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LevelStats {
 	pub time: Duration,
 	pub killed_bees: u32,
 	pub missed_bees: u32,
+	/// How many lives have been lost, to any cause.
+	#[serde(default)]
+	pub lives_lost: u32,
+	/// Why the most recent life was lost, so the level can end with the right result.
+	#[serde(default)]
+	pub last_life_lost: Option<GameResult>,
+	#[serde(default)]
+	pub score: u32,
 	pub result: Option<GameResult>,
+	/// What [`GameRng`](crate::rng::GameRng) was seeded with for this attempt.
+	#[serde(default)]
+	pub seed: u64,
+}
+
+impl LevelStats {
+	/// Average bees killed per second so far.
+	pub fn kill_rate(&self) -> f32 {
+		let secs = self.time.as_secs_f32();
+		if secs > 0.0 {
+			self.killed_bees as f32 / secs
+		} else {
+			0.0
+		}
+	}
 }
 
 #[derive(Resource, Default, Clone, Debug, Serialize, Deserialize)]
 pub struct RunStats {
+	pub started: DateTime<Local>,
+	/// The final stats of the last attempt at each level played during the run.
 	pub levels: IndexMap<String, LevelStats>,
 }
 
+impl RunStats {
+	pub fn new() -> Self {
+		Self {
+			started: Local::now(),
+			levels: default(),
+		}
+	}
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GameResult {
 	Win,
 	OutOfBounds,
+	/// Hit a hazard with the blades.
+	HitHazard,
 	TimedOut,
 	MissedTooMany,
+	KillRateTooLow,
+	OutOfLives,
+	/// Left for the main menu before the level ended.
+	Quit,
 }
 
 pub fn end_level(
 	result: In<GameResult>,
+	mut cmds: Commands,
 	level: Res<Level>,
+	level_list: Res<LevelList>,
 	mut stats: ResMut<LevelStats>,
 	mut run_stats: ResMut<RunStats>,
 	mut next_state: ResMut<NextState<GameState>>,
+	mut fixed_time: ResMut<Time<Fixed>>,
 ) {
+	// Any more fixed steps this frame would keep changing the stats after they were recorded.
+	let overstep = fixed_time.overstep();
+	fixed_time.discard_overstep(overstep);
 	stats.result = Some(*result);
 	run_stats.levels.insert(level.name.clone(), stats.clone());
+	let last_level = level_list.1 + 1 >= level_list.len();
+	if *result != GameResult::Win || last_level {
+		cmds.run_system_cached(save_run);
+	}
 	next_state.set(GameState::LevelEnd);
 }
 
+/// Records the current run in [`SaveData::runs`], replacing any earlier record of the same run.
+pub fn save_run(run_stats: Res<RunStats>, save: Option<ResMut<Persistent<SaveData>>>) {
+	let Some(mut save) = save else {
+		return;
+	};
+	if run_stats.levels.is_empty() {
+		return;
+	}
+	if let Err(e) = save.update(|save| {
+		save.runs.insert(run_stats.started, run_stats.clone());
+	}) {
+		error!("Failed to save run: {e}");
+	}
+}
+
 pub fn tick_stats_time(mut stats: ResMut<LevelStats>, t: Res<Time>) {
 	stats.time += t.delta();
 }
@@ -0,0 +1,269 @@
+use jeremy_bearimy::levels::{Goal, GoalStatus};
+use jeremy_bearimy::stats::{GameResult, LevelStats};
+use std::time::Duration;
+
+const DURATION: u64 = 60;
+
+/// Stats `secs` into a level which lasts [`DURATION`] seconds.
+#[derive(Clone, Copy)]
+struct Stats {
+	secs: u64,
+	killed: u32,
+	missed: u32,
+	lives_lost: u32,
+	score: u32,
+	last_life_lost: Option<GameResult>,
+}
+
+impl From<Stats> for LevelStats {
+	fn from(stats: Stats) -> Self {
+		LevelStats {
+			time: Duration::from_secs(stats.secs),
+			killed_bees: stats.killed,
+			missed_bees: stats.missed,
+			lives_lost: stats.lives_lost,
+			score: stats.score,
+			last_life_lost: stats.last_life_lost,
+			..Default::default()
+		}
+	}
+}
+
+const MID: Stats = Stats {
+	secs: 30,
+	killed: 0,
+	missed: 0,
+	lives_lost: 0,
+	score: 0,
+	last_life_lost: None,
+};
+const END: Stats = Stats {
+	secs: DURATION,
+	..MID
+};
+
+fn check_statuses(cases: &[(Goal, Stats, GoalStatus)]) {
+	for (goal, stats, expected) in cases {
+		let status = goal.status(&(*stats).into(), Duration::from_secs(DURATION));
+		assert_eq!(
+			status, *expected,
+			"{goal:?} after {}s with {} killed, {} missed, {} lives lost and {} score",
+			stats.secs, stats.killed, stats.missed, stats.lives_lost, stats.score
+		);
+	}
+}
+
+#[test]
+fn single_goals() {
+	use GoalStatus::*;
+	check_statuses(&[
+		(Goal::Time, MID, InProgress),
+		(Goal::Time, END, Won),
+		(Goal::Bees(10), Stats { killed: 9, ..MID }, InProgress),
+		(Goal::Bees(10), Stats { killed: 10, ..MID }, Won),
+		(
+			Goal::Bees(10),
+			Stats { killed: 9, ..END },
+			Failed(GameResult::TimedOut),
+		),
+		(Goal::MaxMissed(5), Stats { missed: 4, ..MID }, Satisfied),
+		(
+			Goal::MaxMissed(5),
+			Stats { missed: 5, ..MID },
+			Failed(GameResult::MissedTooMany),
+		),
+		(Goal::MaxMissed(5), Stats { missed: 4, ..END }, Won),
+		// Only decided at the end, since the average can still change.
+		(
+			Goal::KillRate(2.0),
+			Stats { killed: 200, ..MID },
+			InProgress,
+		),
+		(Goal::KillRate(2.0), Stats { killed: 120, ..END }, Won),
+		(
+			Goal::KillRate(2.0),
+			Stats { killed: 119, ..END },
+			Failed(GameResult::KillRateTooLow),
+		),
+		(
+			Goal::Lives(3),
+			Stats {
+				lives_lost: 2,
+				..MID
+			},
+			Satisfied,
+		),
+		(
+			Goal::Lives(3),
+			Stats {
+				lives_lost: 3,
+				..MID
+			},
+			Failed(GameResult::OutOfLives),
+		),
+		(
+			Goal::Lives(3),
+			Stats {
+				lives_lost: 2,
+				..END
+			},
+			Won,
+		),
+		(Goal::Score(100), Stats { score: 99, ..MID }, InProgress),
+		(Goal::Score(100), Stats { score: 100, ..MID }, Won),
+		(
+			Goal::Score(100),
+			Stats { score: 99, ..END },
+			Failed(GameResult::TimedOut),
+		),
+	]);
+}
+
+#[test]
+fn composite_goals() {
+	use GoalStatus::*;
+	let all = || Goal::All(vec![Goal::MaxMissed(5), Goal::Bees(10)]);
+	let any = || Goal::Any(vec![Goal::MaxMissed(5), Goal::Score(100)]);
+	check_statuses(&[
+		(all(), Stats { killed: 9, ..MID }, InProgress),
+		(all(), Stats { killed: 10, ..MID }, Won),
+		(
+			all(),
+			Stats {
+				killed: 10,
+				missed: 5,
+				..MID
+			},
+			Failed(GameResult::MissedTooMany),
+		),
+		(
+			Goal::All(vec![Goal::MaxMissed(5), Goal::Lives(3)]),
+			MID,
+			Satisfied,
+		),
+		// Won by the time running out, so not before then.
+		(
+			Goal::All(vec![Goal::Time, Goal::Bees(10)]),
+			Stats { killed: 10, ..MID },
+			InProgress,
+		),
+		(
+			Goal::All(vec![Goal::Time, Goal::Bees(10)]),
+			Stats { killed: 10, ..END },
+			Won,
+		),
+		(any(), MID, Satisfied),
+		(any(), Stats { score: 100, ..MID }, Won),
+		// Still winnable by scoring, even with too many bees missed.
+		(any(), Stats { missed: 5, ..MID }, InProgress),
+		(
+			any(),
+			Stats { missed: 5, ..END },
+			Failed(GameResult::MissedTooMany),
+		),
+		(any(), END, Won),
+	]);
+}
+
+#[test]
+fn nested_goals() {
+	use GoalStatus::*;
+	let goal = Goal::Any(vec![
+		Goal::All(vec![Goal::Time, Goal::Bees(10)]),
+		Goal::Score(100),
+	]);
+	check_statuses(&[
+		(goal.clone(), Stats { killed: 10, ..MID }, InProgress),
+		(goal.clone(), Stats { score: 100, ..MID }, Won),
+		(goal.clone(), Stats { killed: 10, ..END }, Won),
+		(
+			goal,
+			Stats {
+				killed: 9,
+				score: 99,
+				..END
+			},
+			Failed(GameResult::TimedOut),
+		),
+	]);
+}
+
+#[test]
+fn lives_are_the_most_any_goal_allows() {
+	assert_eq!(Goal::Time.lives(), 1);
+	assert_eq!(Goal::Lives(3).lives(), 3);
+	let goal = Goal::All(vec![
+		Goal::Lives(2),
+		Goal::Any(vec![Goal::Bees(10), Goal::Lives(5)]),
+	]);
+	assert_eq!(goal.lives(), 5);
+}
+
+#[test]
+fn level_results() {
+	let duration = Duration::from_secs(DURATION);
+	let cases = [
+		(
+			Goal::Bees(10),
+			Stats { killed: 10, ..MID },
+			Some(GameResult::Win),
+		),
+		(Goal::Bees(10), MID, None),
+		(Goal::Bees(10), END, Some(GameResult::TimedOut)),
+		// With one life, the level ends with however it was lost.
+		(
+			Goal::Bees(10),
+			Stats {
+				lives_lost: 1,
+				..MID
+			},
+			Some(GameResult::OutOfBounds),
+		),
+		(
+			Goal::Bees(10),
+			Stats {
+				lives_lost: 1,
+				last_life_lost: Some(GameResult::HitHazard),
+				..MID
+			},
+			Some(GameResult::HitHazard),
+		),
+		(
+			Goal::Lives(3),
+			Stats {
+				lives_lost: 2,
+				..MID
+			},
+			None,
+		),
+		(
+			Goal::Lives(3),
+			Stats {
+				lives_lost: 3,
+				last_life_lost: Some(GameResult::HitHazard),
+				..MID
+			},
+			Some(GameResult::OutOfLives),
+		),
+		(
+			Goal::Lives(3),
+			Stats {
+				lives_lost: 2,
+				..END
+			},
+			Some(GameResult::Win),
+		),
+		// Empty composite goals still end when the time is up.
+		(Goal::All(vec![]), END, Some(GameResult::Win)),
+		(Goal::Any(vec![]), END, Some(GameResult::TimedOut)),
+	];
+	for (goal, stats, expected) in cases {
+		assert_eq!(
+			goal.result(&stats.into(), duration),
+			expected,
+			"{goal:?} after {}s with {} lives lost",
+			stats.secs,
+			stats.lives_lost
+		);
+	}
+}
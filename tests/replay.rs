@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use jeremy_bearimy::replay::Replay;
+use jeremy_bearimy::sim::{ScriptedInput, SimConfig, SimView, load_level_list, simulate};
+use std::time::Duration;
+
+/// Enough input to kill some bees, so there is something for a replay to get wrong.
+fn chase_nearest_bee(view: &SimView) -> Vec2 {
+	view.bees
+		.iter()
+		.map(|bee| bee.position - view.player)
+		.min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+		.map_or(-view.player, |offset| offset)
+		.normalize_or_zero()
+}
+
+#[test]
+fn replays_reproduce_their_stats() {
+	let level_list = load_level_list("assets").unwrap();
+	let config = SimConfig {
+		level: level_list[0].name.clone(),
+		seed: Some(7),
+		duration: Duration::from_secs(10),
+		..default()
+	};
+	let recorded = simulate(&config, chase_nearest_bee).unwrap();
+	assert!(recorded.stats.killed_bees > 0);
+
+	let path =
+		std::env::temp_dir().join(format!("jeremy-bearimy-replay-{}.ron", std::process::id()));
+	recorded.replay.save(&path).unwrap();
+	let replay = Replay::load(&path).unwrap();
+	std::fs::remove_file(&path).unwrap();
+
+	let replayed = simulate(
+		&SimConfig {
+			level: replay.level.clone(),
+			seed: Some(replay.seed),
+			..config
+		},
+		ScriptedInput::from(&replay),
+	)
+	.unwrap();
+	assert_eq!(replayed.stats, replay.stats);
+	assert_eq!(replayed.replay.inputs, replay.inputs);
+}
@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use bevy::reflect::PartialReflect;
+use jeremy_bearimy::bees::Bee;
+use jeremy_bearimy::loading::asset_load_failure;
+use jeremy_bearimy::map::TimelinePosition;
+use jeremy_bearimy::portals::{Portal, SpawnPattern};
+use jeremy_bearimy::sim::{headless_app, wait_for};
+
+fn app(asset_dir: impl Into<String>) -> App {
+	let mut app = headless_app(asset_dir);
+	app.finish();
+	app.cleanup();
+	app
+}
+
+/// Loads `path` with the scene loader, panicking if it fails.
+fn load_scene(app: &mut App, path: &str) -> Handle<DynamicScene> {
+	let handle = app
+		.world()
+		.resource::<AssetServer>()
+		.load::<DynamicScene>(path.to_owned());
+	wait_for(app, |world| {
+		let server = world.resource::<AssetServer>();
+		if let Some(reason) = asset_load_failure(server, &handle) {
+			panic!("Failed to load {path}: {reason}");
+		}
+		server.is_loaded_with_dependencies(&handle).then_some(())
+	})
+	.unwrap();
+	handle
+}
+
+#[test]
+fn default_level_scene_loads() {
+	let mut app = app("assets");
+	let handle = load_scene(&mut app, "levels/empty.scn.ron");
+	let scenes = app.world().resource::<Assets<DynamicScene>>();
+	assert!(scenes.get(&handle).unwrap().entities.is_empty());
+}
+
+#[test]
+fn gameplay_components_round_trip_through_scene_files() {
+	let dir = std::env::temp_dir().join(format!("jeremy-bearimy-scenes-{}", std::process::id()));
+	std::fs::create_dir_all(&dir).unwrap();
+	let mut app = app(dir.to_string_lossy());
+
+	let portal = Portal {
+		remaining_spawns: Some(7),
+		pattern: SpawnPattern::Spiral {
+			count: 3,
+			angular_velocity: 1.5,
+		},
+		spawn_offset: 48.0,
+		..default()
+	};
+	let timeline = TimelinePosition {
+		t: 0.25,
+		speed: 0.75,
+	};
+	let bee = Bee {
+		speed: 250.0,
+		radius: 24.0,
+		health: Some(3),
+		score: 5,
+	};
+	let id = app
+		.world_mut()
+		.spawn((portal.clone(), timeline, bee.clone()))
+		.id();
+	let scene = DynamicSceneBuilder::from_world(app.world())
+		.allow_component::<Portal>()
+		.allow_component::<TimelinePosition>()
+		.allow_component::<Bee>()
+		.extract_entity(id)
+		.build();
+	let ron = scene
+		.serialize(&app.world().resource::<AppTypeRegistry>().read())
+		.unwrap();
+	std::fs::write(dir.join("round_trip.scn.ron"), ron).unwrap();
+
+	let handle = load_scene(&mut app, "round_trip.scn.ron");
+	let scenes = app.world().resource::<Assets<DynamicScene>>();
+	let loaded = scenes.get(&handle).unwrap();
+	assert_eq!(loaded.entities.len(), 1);
+	let components = &loaded.entities[0].components;
+	assert_eq!(components.len(), 3);
+	let originals: [&dyn PartialReflect; 3] = [&portal, &timeline, &bee];
+	for original in originals {
+		let component = components
+			.iter()
+			.find(|component| {
+				component
+					.get_represented_type_info()
+					.map(|info| info.type_id())
+					== original
+						.get_represented_type_info()
+						.map(|info| info.type_id())
+			})
+			.unwrap_or_else(|| panic!("{original:?} is missing from the loaded scene"));
+		assert_eq!(
+			original.reflect_partial_eq(&**component),
+			Some(true),
+			"{original:?} was loaded as {component:?}"
+		);
+	}
+	std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use jeremy_bearimy::sim::{ScriptedInput, SimConfig, SimError, load_level_list, simulate};
+use jeremy_bearimy::stats::GameResult;
+use std::time::Duration;
+
+fn first_level() -> String {
+	load_level_list("assets").unwrap()[0].name.clone()
+}
+
+#[test]
+fn keyframes_are_held_until_the_next() {
+	let step = Duration::from_secs(1);
+	let script = ScriptedInput::from_keyframes(
+		[(Duration::ZERO, Vec2::X), (Duration::from_secs(2), Vec2::Y)],
+		step,
+	);
+	assert_eq!(script.steps, [Vec2::X, Vec2::X]);
+	assert_eq!(script.after, Vec2::Y);
+
+	let script = ScriptedInput::from_keyframes([(Duration::from_secs(1), Vec2::X)], step);
+	assert_eq!(script.steps, [Vec2::ZERO]);
+	assert_eq!(script.after, Vec2::X);
+}
+
+#[test]
+fn scripted_input_reaches_a_result() {
+	let config = SimConfig {
+		level: first_level(),
+		seed: Some(1),
+		duration: Duration::from_secs(60),
+		..default()
+	};
+	// Holding one direction leaves the map long before the level would otherwise end.
+	let timestep = Time::<Fixed>::default().timestep();
+	let input = ScriptedInput::from_keyframes([(Duration::ZERO, Vec2::X)], timestep);
+	let outcome = simulate(&config, input).unwrap();
+	assert_eq!(outcome.result, Some(GameResult::OutOfBounds));
+	assert_eq!(outcome.stats.result, outcome.result);
+	assert!(outcome.stats.time < config.duration);
+	assert_eq!(outcome.stats.seed, 1);
+	assert!(outcome.replay.inputs.iter().all(|&input| input == Vec2::X));
+}
+
+#[test]
+fn simulations_stop_after_their_duration() {
+	let config = SimConfig {
+		level: first_level(),
+		seed: Some(2),
+		duration: Duration::from_secs(2),
+		..default()
+	};
+	let outcome = simulate(&config, ScriptedInput::default()).unwrap();
+	assert_eq!(outcome.result, None);
+	assert!(outcome.stats.time >= config.duration);
+}
+
+#[test]
+fn unknown_levels_are_an_error() {
+	let config = SimConfig {
+		level: "Not a level".into(),
+		..default()
+	};
+	let result = simulate(&config, ScriptedInput::default());
+	assert!(matches!(result, Err(SimError::UnknownLevel(name)) if name == config.level));
+}
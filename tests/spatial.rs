@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use jeremy_bearimy::spatial::SpatialGrid;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+const CELL_SIZE: f32 = 32.0;
+
+/// Checks every query against checking every point.
+fn assert_matches_brute_force(points: &[Vec2], queries: &[(Vec2, f32)]) {
+	let mut grid = SpatialGrid::new(CELL_SIZE);
+	grid.rebuild(points.iter().copied().zip(0usize..));
+	for &(center, radius) in queries {
+		let mut found = grid
+			.query_radius(center, radius)
+			.map(|(_, &i)| i)
+			.collect::<Vec<_>>();
+		found.sort_unstable();
+		let expected = points
+			.iter()
+			.enumerate()
+			.filter(|(_, pos)| pos.distance_squared(center) <= radius * radius)
+			.map(|(i, _)| i)
+			.collect::<Vec<_>>();
+		assert_eq!(found, expected, "query at {center} with radius {radius}");
+	}
+}
+
+#[test]
+fn queries_match_brute_force() {
+	let mut rng = ChaCha8Rng::seed_from_u64(1);
+	for _ in 0..20 {
+		let points = (0..rng.random_range(0..500))
+			.map(|_| {
+				Vec2::new(
+					rng.random_range(-300.0..200.0),
+					rng.random_range(-100.0..400.0),
+				)
+			})
+			.collect::<Vec<_>>();
+		// Including queries which only partly overlap the grid, or miss it entirely.
+		let queries = (0..100)
+			.map(|_| {
+				let center = Vec2::new(
+					rng.random_range(-800.0..800.0),
+					rng.random_range(-800.0..800.0),
+				);
+				(center, rng.random_range(0.0..200.0))
+			})
+			.collect::<Vec<_>>();
+		assert_matches_brute_force(&points, &queries);
+	}
+}
+
+#[test]
+fn points_on_cell_borders_are_found() {
+	// Every corner of a few cells, including negative ones, where flooring rounds away from zero.
+	let points = (-3..=3)
+		.flat_map(|x| (-3..=3).map(move |y| Vec2::new(x as f32, y as f32) * CELL_SIZE))
+		.collect::<Vec<_>>();
+	let mut queries = Vec::new();
+	for &point in &points {
+		// Exactly on the point, and with the point exactly on the edge of the circle.
+		queries.push((point, 0.0));
+		queries.push((point + Vec2::X * CELL_SIZE, CELL_SIZE));
+		queries.push((point - Vec2::Y * CELL_SIZE, CELL_SIZE));
+		queries.push((point, CELL_SIZE * 0.5));
+	}
+	assert_matches_brute_force(&points, &queries);
+}
+
+#[test]
+fn queries_outside_the_grid_find_nothing() {
+	let points = [Vec2::ZERO, Vec2::splat(CELL_SIZE * 2.5)];
+	let mut grid = SpatialGrid::new(CELL_SIZE);
+	grid.rebuild(points.into_iter().zip(0usize..));
+	for center in [
+		Vec2::new(-1000.0, 0.0),
+		Vec2::new(1000.0, 0.0),
+		Vec2::new(0.0, -1000.0),
+		Vec2::new(0.0, 1000.0),
+		Vec2::splat(-1000.0),
+		Vec2::splat(1000.0),
+	] {
+		assert_eq!(
+			grid.query_radius(center, 100.0).count(),
+			0,
+			"query at {center}"
+		);
+	}
+
+	let empty = SpatialGrid::<()>::new(CELL_SIZE);
+	assert_eq!(empty.query_radius(Vec2::ZERO, 100.0).count(), 0);
+}